@@ -0,0 +1,458 @@
+//! Evaluation of address match lists the way `named` does, to answer
+//! questions such as whether a client may transfer a zone.
+//!
+//! Elements are tried in order and the first one that matches decides:
+//! the request is allowed unless the element is negated. A nested list or
+//! named ACL matches only when something in it matches without negation,
+//! so `!` never turns into an allow through a double negation.
+
+use std::{
+    net::IpAddr,
+    path::{Path, PathBuf},
+};
+
+use crate::bind::obj::ObjRef;
+use crate::dns::same_name;
+
+/// Who is asking.
+#[derive(Debug, Clone)]
+pub struct Request<'r> {
+    pub addr: IpAddr,
+    /// The name of the TSIG key the request is signed with.
+    pub key: Option<&'r str>,
+    /// The addresses and prefix lengths of the server's interfaces, which
+    /// `localhost` and `localnets` stand for. `named` includes its loopback
+    /// interface, so callers usually should too.
+    pub interfaces: &'r [(IpAddr, u32)],
+}
+
+/// The element that decided the outcome of an address match list.
+#[derive(Clone)]
+pub struct AclMatch<'a> {
+    /// False when the element, or a list it was found through, is negated.
+    pub allowed: bool,
+    /// The innermost element that matched.
+    pub element: ObjRef<'a>,
+    /// The elements the match was found through as written, outermost
+    /// first and ending with `element`, e.g. `["!trusted", "10.0.0.0/8"]`.
+    pub path: Vec<String>,
+    pub file: Option<PathBuf>,
+    /// 1-based, as BIND reports lines.
+    pub line: u32,
+}
+
+impl AclMatch<'_> {
+    /// `file:line` of the element, for messages.
+    pub fn location(&self, conf_path: &Path) -> String {
+        let file = self.file.as_deref().unwrap_or(conf_path);
+        format!("{}:{}", file.display(), self.line)
+    }
+}
+
+pub struct AclEvaluator<'a> {
+    root: ObjRef<'a>,
+}
+
+impl<'a> AclEvaluator<'a> {
+    /// An evaluator resolving ACL names through the top-level `acl`
+    /// statements of `root`.
+    pub fn new(root: ObjRef<'a>) -> Self {
+        AclEvaluator { root }
+    }
+
+    /// Evaluates an address match list. `None` means no element matched,
+    /// which `named` treats as a denial.
+    pub fn evaluate(&self, aml: ObjRef<'a>, request: &Request) -> Option<AclMatch<'a>> {
+        self.evaluate_in(aml, request, &mut vec![])
+    }
+
+    fn evaluate_in(
+        &self,
+        aml: ObjRef<'a>,
+        request: &Request,
+        seen: &mut Vec<&'a str>,
+    ) -> Option<AclMatch<'a>> {
+        for element in elements(aml) {
+            let (negated, inner) = match element.tuple_get("negated") {
+                Some(inner) => (true, inner),
+                None => (false, element),
+            };
+            let Some(mut found) = self.element(inner, request, seen) else {
+                continue;
+            };
+
+            let direct = found.element.as_ptr() == inner.as_ptr();
+            if negated {
+                found.allowed = !found.allowed;
+                if direct {
+                    found.path.clear();
+                }
+                found.path.insert(0, format!("!{}", describe(inner)));
+            } else if !direct {
+                found.path.insert(0, describe(inner));
+            }
+
+            return Some(found);
+        }
+
+        None
+    }
+
+    /// Matches a single element, without its negation.
+    fn element(
+        &self,
+        element: ObjRef<'a>,
+        request: &Request,
+        seen: &mut Vec<&'a str>,
+    ) -> Option<AclMatch<'a>> {
+        let matched = |allowed: bool| AclMatch {
+            allowed,
+            element,
+            path: vec![describe(element)],
+            file: element.file().map(PathBuf::from),
+            line: element.line(),
+        };
+
+        if element.is_keyref() {
+            let name = element.as_str()?;
+            let key = request.key?;
+            return same_name(key, name).then(|| matched(true));
+        }
+        if let Some((prefix, len)) = element.as_netprefix() {
+            return in_prefix(request.addr, prefix, len).then(|| matched(true));
+        }
+        if element.is_list() {
+            return self.nested(element, request, seen);
+        }
+
+        let name = element.as_str()?;
+        match name.to_ascii_lowercase().as_str() {
+            "any" => Some(matched(true)),
+            // none is a negated any
+            "none" => Some(matched(false)),
+            "localhost" => {
+                let addr = request.addr.to_canonical();
+                let local = request.interfaces.iter().any(|(a, _)| *a == addr);
+                local.then(|| matched(true))
+            }
+            "localnets" => {
+                let local = request
+                    .interfaces
+                    .iter()
+                    .any(|(a, len)| in_prefix(request.addr, *a, *len));
+                local.then(|| matched(true))
+            }
+            _ => self.named(name, seen, |acl, seen| self.nested(acl, request, seen)),
+        }
+    }
+
+    /// A nested list or named ACL matches only when an element of it
+    /// allows the request.
+    fn nested(
+        &self,
+        aml: ObjRef<'a>,
+        request: &Request,
+        seen: &mut Vec<&'a str>,
+    ) -> Option<AclMatch<'a>> {
+        self.evaluate_in(aml, request, seen)
+            .filter(|found| found.allowed)
+    }
+
+    /// Finds the element through which the list allows every client that
+    /// does not sign with a key: `any`, `!none`, or a nested list or ACL
+    /// that does. `None` when an element before it may deny some clients.
+    pub fn allows_any(&self, aml: ObjRef<'a>) -> Option<ObjRef<'a>> {
+        self.allows_any_in(aml, &mut vec![])
+    }
+
+    fn allows_any_in(&self, aml: ObjRef<'a>, seen: &mut Vec<&'a str>) -> Option<ObjRef<'a>> {
+        for element in elements(aml) {
+            if let Some(inner) = element.tuple_get("negated") {
+                // a negated key only denies clients signing with it
+                if inner.is_keyref() {
+                    continue;
+                }
+                if is_named(inner, "none") {
+                    return Some(element);
+                }
+                return None;
+            }
+            if element.is_keyref() || element.as_netprefix().is_some() {
+                continue;
+            }
+            if element.is_list() {
+                match self.allows_any_in(element, seen) {
+                    Some(found) => return Some(found),
+                    None => continue,
+                }
+            }
+
+            let Some(name) = element.as_str() else {
+                continue;
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "any" => return Some(element),
+                "none" => return None,
+                "localhost" | "localnets" => {}
+                _ => {
+                    let found = self.named(name, seen, |acl, seen| self.allows_any_in(acl, seen));
+                    if found.is_some() {
+                        return Some(element);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds the element through which the list allows some clients by
+    /// address alone: an address prefix, `any`, `localhost`, `localnets`,
+    /// or a nested list or ACL holding one that an element before it does
+    /// not rule out.
+    pub fn allows_by_address(&self, aml: ObjRef<'a>) -> Option<ObjRef<'a>> {
+        self.allows_by_address_in(aml, &mut vec![])
+    }
+
+    fn allows_by_address_in(&self, aml: ObjRef<'a>, seen: &mut Vec<&'a str>) -> Option<ObjRef<'a>> {
+        for element in elements(aml) {
+            // negations only deny, apart from the ones of any and none
+            if let Some(inner) = element.tuple_get("negated") {
+                if inner.is_keyref() {
+                    continue;
+                }
+                if is_named(inner, "none") {
+                    return Some(element);
+                }
+                if is_named(inner, "any") {
+                    return None;
+                }
+                continue;
+            }
+            if element.is_keyref() {
+                continue;
+            }
+            if element.as_netprefix().is_some() {
+                return Some(element);
+            }
+            if element.is_list() {
+                match self.allows_by_address_in(element, seen) {
+                    Some(found) => return Some(found),
+                    None => continue,
+                }
+            }
+
+            let Some(name) = element.as_str() else {
+                continue;
+            };
+            match name.to_ascii_lowercase().as_str() {
+                "none" => return None,
+                "any" | "localhost" | "localnets" => return Some(element),
+                _ => {
+                    let found =
+                        self.named(name, seen, |acl, seen| self.allows_by_address_in(acl, seen));
+                    if found.is_some() {
+                        return Some(element);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Runs `f` on the list of the ACL named `name`, unless that ACL is
+    /// already being looked at, as when it refers to itself.
+    fn named<T>(
+        &self,
+        name: &'a str,
+        seen: &mut Vec<&'a str>,
+        f: impl FnOnce(ObjRef<'a>, &mut Vec<&'a str>) -> Option<T>,
+    ) -> Option<T> {
+        if seen.contains(&name) {
+            return None;
+        }
+        let acl = self.acl(name)?;
+
+        seen.push(name);
+        let found = f(acl, seen);
+        seen.pop();
+        found
+    }
+
+    /// The address match list of the top-level `acl` named `name`.
+    fn acl(&self, name: &str) -> Option<ObjRef<'a>> {
+        let acls = self.root.map_get("acl")?.list();
+        let acl = acls.into_iter().find(|acl| {
+            acl.tuple_get("name")
+                .and_then(|n| n.as_str())
+                .is_some_and(|n| n.eq_ignore_ascii_case(name))
+        })?;
+
+        acl.tuple_get("value")
+    }
+}
+
+/// The address match list of `clause` that applies to a zone: the zone's
+/// own, else that of its view, else that of `options`. `None` when the
+/// zone does not exist or the clause is not set, so `named`'s default
+/// applies.
+pub fn zone_acl<'a>(
+    root: ObjRef<'a>,
+    clause: &str,
+    zone: &str,
+    view: Option<&str>,
+) -> Option<ObjRef<'a>> {
+    let view = match view {
+        Some(name) => {
+            let views = root.map_get("view")?.list();
+            let view = views.into_iter().find(|v| {
+                v.tuple_get("name")
+                    .and_then(|n| n.as_str())
+                    .is_some_and(|n| n == name)
+            })?;
+            Some(view.tuple_get("options")?)
+        }
+        None => None,
+    };
+
+    let zones = view.unwrap_or(root).map_get("zone")?.list();
+    let zone = zones.into_iter().find(|z| {
+        z.tuple_get("name")
+            .and_then(|n| n.as_str())
+            .is_some_and(|n| same_name(n, zone))
+    })?;
+
+    [zone.tuple_get("options"), view, root.map_get("options")]
+        .into_iter()
+        .flatten()
+        .find_map(|map| map.map_get(clause))
+}
+
+/// The elements of an address match list. `allow-transfer` and similar
+/// clauses take a port and transport before the list.
+fn elements(aml: ObjRef<'_>) -> Vec<ObjRef<'_>> {
+    aml.tuple_get("aml").unwrap_or(aml).list()
+}
+
+fn is_named(element: ObjRef, name: &str) -> bool {
+    element
+        .as_str()
+        .is_some_and(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// Whether `addr` is in `prefix/len`. IPv4-mapped IPv6 clients match IPv4
+/// prefixes, as in `named`.
+fn in_prefix(addr: IpAddr, prefix: IpAddr, len: u32) -> bool {
+    match (addr.to_canonical(), prefix) {
+        (IpAddr::V4(addr), IpAddr::V4(prefix)) => {
+            let mask = u32::MAX.checked_shl(32 - len.min(32)).unwrap_or(0);
+            u32::from(addr) & mask == u32::from(prefix) & mask
+        }
+        (IpAddr::V6(addr), IpAddr::V6(prefix)) => {
+            let mask = u128::MAX.checked_shl(128 - len.min(128)).unwrap_or(0);
+            u128::from(addr) & mask == u128::from(prefix) & mask
+        }
+        _ => false,
+    }
+}
+
+/// An element as written in the configuration.
+fn describe(element: ObjRef) -> String {
+    if let Some(inner) = element.tuple_get("negated") {
+        return format!("!{}", describe(inner));
+    }
+    if element.is_keyref() {
+        return format!("key \"{}\"", element.as_str().unwrap_or_default());
+    }
+    if let Some((prefix, len)) = element.as_netprefix() {
+        return format!("{}/{}", prefix, len);
+    }
+    if element.is_list() {
+        let elements = element.list().into_iter().map(describe).collect::<Vec<_>>();
+        return format!("{{ {}; }}", elements.join("; "));
+    }
+
+    element.as_str().unwrap_or("?").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+
+    #[test]
+    fn test_in_prefix() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+
+        assert!(in_prefix(ip("10.2.3.4"), ip("10.0.0.0"), 8));
+        assert!(!in_prefix(ip("11.2.3.4"), ip("10.0.0.0"), 8));
+        assert!(in_prefix(ip("192.0.2.1"), ip("0.0.0.0"), 0));
+        assert!(in_prefix(ip("::ffff:10.2.3.4"), ip("10.2.3.4"), 32));
+        assert!(in_prefix(ip("2001:db8::1"), ip("2001:db8::"), 32));
+        assert!(!in_prefix(ip("2001:db9::1"), ip("2001:db8::"), 32));
+        assert!(!in_prefix(ip("10.2.3.4"), ip("::"), 0));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+acl "trusted" { !10.9.0.0/16; 10.0.0.0/8; };
+options { allow-transfer { none; }; };
+view "internal" {
+    allow-transfer { !10.1.0.0/16; trusted; key "xfer-key"; localnets; };
+    zone "example.com" { type primary; file "db.example.com"; };
+};
+"#,
+            )
+            .unwrap();
+        let root = obj.root();
+        let evaluator = AclEvaluator::new(root);
+        let aml = zone_acl(root, "allow-transfer", "example.com.", Some("internal")).unwrap();
+
+        let interfaces = [("192.168.1.1".parse().unwrap(), 24)];
+        let check = |addr: &str, key| {
+            let request = Request {
+                addr: addr.parse().unwrap(),
+                key,
+                interfaces: &interfaces,
+            };
+            evaluator
+                .evaluate(aml, &request)
+                .map(|found| (found.allowed, found.path.join(" > "), found.line))
+        };
+
+        assert_eq!(
+            check("10.2.3.4", None),
+            Some((true, "trusted > 10.0.0.0/8".to_string(), 2))
+        );
+        assert_eq!(
+            check("10.1.2.3", Some("xfer-key")),
+            Some((false, "!10.1.0.0/16".to_string(), 5))
+        );
+        // the negated element of trusted does not match, so the key does
+        assert_eq!(
+            check("10.9.2.3", Some("xfer-key.")),
+            Some((true, "key \"xfer-key\"".to_string(), 5))
+        );
+        assert_eq!(
+            check("192.168.1.77", None),
+            Some((true, "localnets".to_string(), 5))
+        );
+        assert_eq!(check("172.16.0.1", None), None);
+
+        // the negated prefix may deny some clients before trusted allows
+        assert!(evaluator.allows_any(aml).is_none());
+        let by_address = evaluator.allows_by_address(aml).unwrap();
+        assert_eq!(by_address.as_str(), Some("trusted"));
+
+        let aml = zone_acl(root, "allow-transfer", "example.com", None);
+        assert!(aml.is_none());
+    }
+}
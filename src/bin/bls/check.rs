@@ -0,0 +1,123 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use bind_parser::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+use bind_parser::check::conf::check_conf;
+use bind_parser::check::lint::{rule, Category};
+use bind_parser::check::suppress::apply_suppressions;
+use bind_parser::check::zone_file::{check_zone_file, zone_log_diagnostics};
+use bind_parser::check::zones::check_all_zones;
+use bind_parser::config::ProjectConfig;
+use bind_parser::diagnostic::Severity;
+use bind_parser::lsp::consistency::consistency_diagnostics;
+use bind_parser::lsp::document::DocumentKind;
+use bind_parser::lsp::path_to_uri;
+use bind_parser::lsp::serial::{serial_diagnostics, HeadVersions};
+use bind_parser::report::{to_json, to_sarif};
+
+use crate::USAGE;
+
+/// Checks configurations and zone files with the checks the language server
+/// runs, printing one `file:line:col: severity: message` line per
+/// diagnostic, or all of them as JSON or SARIF. With `-z` the zones of each
+/// configuration are loaded too, and with `--security` only errors and the
+/// findings of security rules are reported.
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut load_zones = false;
+    let mut include_secondaries = false;
+    let mut chroot = None;
+    let mut format = "text";
+    let mut security = false;
+    let mut files = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-z" => load_zones = true,
+            "--secondaries" => include_secondaries = true,
+            "-t" => chroot = Some(args.next().ok_or(USAGE)?),
+            "--format" => format = args.next().ok_or(USAGE)?,
+            "--security" => security = true,
+            _ => files.push(Path::new(arg)),
+        }
+    }
+    if files.is_empty() {
+        return Err(USAGE.into());
+    }
+    if !["text", "json", "sarif"].contains(&format) {
+        return Err(format!("unsupported format: {}", format).into());
+    }
+
+    let mem = IscMem::new();
+    let log = IscLog::new(&mem);
+    let parser = IscParser::new(&mem, &log)?;
+
+    let mut heads = HeadVersions::default();
+    let mut all = vec![];
+    for path in files {
+        let text =
+            fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+        let mut config = ProjectConfig::discover(path.parent().unwrap_or(Path::new(".")))?;
+        if let Some(chroot) = chroot {
+            config.chroot = Some(std::path::absolute(chroot)?);
+        }
+
+        let uri = path_to_uri(path).ok_or_else(|| format!("{}: invalid path", path.display()))?;
+        let diagnostics = match DocumentKind::detect(&uri, None, &text) {
+            DocumentKind::Zone => {
+                let mut diagnostics = serial_diagnostics(&mut heads, path, &text);
+                diagnostics.extend(check_zone_file(
+                    path,
+                    &text,
+                    &config.zone,
+                    &config.lint_options(),
+                ));
+                diagnostics
+            }
+            DocumentKind::NamedConf => {
+                let check = check_conf(&parser, &log, &mem, &config, path);
+                let mut diagnostics = check.diagnostics;
+                // like named-checkconf -z, zones of a configuration that
+                // failed its checks are not loaded
+                if let (Some(obj), true) = (&check.obj, load_zones && check.valid) {
+                    let checks = check_all_zones(obj.root(), &check.resolver, include_secondaries);
+                    for check in &checks {
+                        let Some(file) = &check.zone.file else {
+                            continue;
+                        };
+                        diagnostics.extend(zone_log_diagnostics(&check.log, file));
+                    }
+
+                    diagnostics.extend(consistency_diagnostics(&checks).into_values().flatten());
+                }
+                diagnostics
+            }
+        };
+
+        let diagnostics = apply_suppressions(diagnostics, path, &text);
+        all.extend(config.apply_severities(diagnostics));
+    }
+
+    // a security review still needs to know the configuration is broken
+    if security {
+        all.retain(|d| {
+            let rule = d.code.as_deref().and_then(rule);
+            d.severity == Severity::Error || rule.is_some_and(|r| r.category == Category::Security)
+        });
+    }
+
+    match format {
+        "json" => println!("{}", to_json(&all)?),
+        "sarif" => println!("{}", to_sarif(&all)?),
+        _ => all.iter().for_each(|d| println!("{}", d)),
+    }
+
+    let errors = all.iter().filter(|d| d.severity == Severity::Error).count();
+
+    if errors > 0 {
+        return Err(format!("{} errors", errors).into());
+    }
+
+    Ok(())
+}
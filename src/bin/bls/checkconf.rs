@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::path::Path;
+
+use bind_parser::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+use bind_parser::check::paths::missing_paths;
+use bind_parser::check::zones::check_all_zones;
+use bind_parser::config::ProjectConfig;
+use bind_parser::diagnostic::split_level;
+use bind_parser::path::IncludedFiles;
+
+use crate::USAGE;
+
+/// Checks a configuration and, with `-z`, loads its zones, printing what
+/// `named-checkconf` would.
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut load_zones = false;
+    let mut include_secondaries = false;
+    let mut chroot = None;
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-z" => load_zones = true,
+            "--secondaries" => include_secondaries = true,
+            "-t" => chroot = Some(args.next().ok_or(USAGE)?),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [path] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    let path = Path::new(path);
+    let config = ProjectConfig::discover(path.parent().unwrap_or(Path::new(".")))?;
+    let mut resolver = config.resolver(path);
+    if let Some(chroot) = chroot {
+        resolver = resolver.with_chroot(chroot);
+    }
+
+    let mem = IscMem::new();
+    let log = IscLog::new(&mem);
+    let parser = IscParser::new(&mem, &log)?;
+
+    let included = IncludedFiles::read(path, &resolver)
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    let Ok(obj) = parser.parse_included(path, &included) else {
+        return Err(without_levels(&included.restore(&log.to_string()))
+            .trim_end()
+            .into());
+    };
+    let valid = obj.check_with(&log, &mem, config.check);
+    print!("{}", without_levels(&included.restore(&log.to_string())));
+
+    let resolver = resolver.with_config(obj.root());
+    for missing in missing_paths(obj.root(), &resolver) {
+        println!(
+            "{}:{}: {} '{}' does not exist ({})",
+            missing.conf_file.as_deref().unwrap_or(path).display(),
+            missing.line,
+            missing.clause,
+            missing.path,
+            missing.resolved.display()
+        );
+    }
+    if !valid {
+        return Err(format!("{}: check failed", path.display()).into());
+    }
+    if !load_zones {
+        return Ok(());
+    }
+
+    let mut failed = 0;
+    for check in check_all_zones(obj.root(), &resolver, include_secondaries) {
+        let zone = &check.zone;
+        let name = match &zone.view {
+            Some(view) => format!("{}/IN/{}", zone.name, view),
+            None => format!("{}/IN", zone.name),
+        };
+
+        print!("{}", without_levels(&check.log));
+        match check.serial {
+            Some(serial) => println!("zone {}: loaded serial {}", name, serial),
+            None => {
+                println!("zone {}: not loaded due to errors.", name);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} zones failed to load", failed).into());
+    }
+
+    Ok(())
+}
+
+/// The log without the level of each line, as `named-checkconf` prints it.
+fn without_levels(log: &str) -> String {
+    log.lines()
+        .map(|line| format!("{}\n", split_level(line).1))
+        .collect()
+}
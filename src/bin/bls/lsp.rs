@@ -0,0 +1,506 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use bind_parser::bind::log::IscLog;
+use bind_parser::bind::mem::IscMem;
+use bind_parser::bind::parser::IscParser;
+use bind_parser::check::conf::check_conf;
+use bind_parser::check::suppress::apply_suppressions;
+use bind_parser::check::zone_file::{check_zone_file, zone_origin};
+use bind_parser::check::zones::check_all_zones;
+use bind_parser::config::{ProjectConfig, CONFIG_FILE};
+use bind_parser::diagnostic::Diagnostic;
+use bind_parser::lsp::code_action::code_actions;
+use bind_parser::lsp::consistency::consistency_diagnostics;
+use bind_parser::lsp::definition::path_definition;
+use bind_parser::lsp::document::{DocumentKind, Documents};
+use bind_parser::lsp::document_symbol::zone_symbols;
+use bind_parser::lsp::folding_range::folding_ranges;
+use bind_parser::lsp::formatting::conf_formatting;
+use bind_parser::lsp::semantic_tokens::{self, semantic_tokens};
+use bind_parser::lsp::serial::{serial_actions, serial_diagnostics, HeadVersions};
+use bind_parser::lsp::workspace_symbol::WorkspaceIndex;
+use bind_parser::lsp::{path_to_uri, uri_to_path};
+use lsp_server::{Connection, ExtractError, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::DidChangeConfiguration;
+use lsp_types::notification::DidChangeTextDocument;
+use lsp_types::notification::DidCloseTextDocument;
+use lsp_types::notification::DidOpenTextDocument;
+use lsp_types::notification::DidSaveTextDocument;
+use lsp_types::notification::Notification as _;
+use lsp_types::notification::PublishDiagnostics;
+use lsp_types::notification::ShowMessage;
+use lsp_types::request::CodeActionRequest;
+use lsp_types::request::DocumentSymbolRequest;
+use lsp_types::request::FoldingRangeRequest;
+use lsp_types::request::Formatting;
+use lsp_types::request::GotoDefinition;
+use lsp_types::request::Request as _;
+use lsp_types::request::SemanticTokensFullRequest;
+use lsp_types::request::SemanticTokensRangeRequest;
+use lsp_types::request::WorkspaceSymbolRequest;
+use lsp_types::CodeActionProviderCapability;
+use lsp_types::DocumentSymbolResponse;
+use lsp_types::FoldingRangeProviderCapability;
+use lsp_types::MessageType;
+use lsp_types::OneOf;
+use lsp_types::PublishDiagnosticsParams;
+use lsp_types::SemanticTokens;
+use lsp_types::SemanticTokensFullOptions;
+use lsp_types::SemanticTokensOptions;
+use lsp_types::SemanticTokensServerCapabilities;
+use lsp_types::ShowMessageParams;
+use lsp_types::Uri;
+use lsp_types::WorkspaceSymbolResponse;
+use lsp_types::{
+    request::DocumentDiagnosticRequest, DiagnosticOptions, DiagnosticServerCapabilities,
+    InitializeParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+};
+
+pub fn run() -> Result<(), Box<dyn Error + Sync + Send>> {
+    eprintln!("starting generic LSP server");
+    let (connection, io_threads) = Connection::stdio();
+
+    let server_capabilities = serde_json::to_value(&ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+            ..Default::default()
+        })),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        workspace_symbol_provider: Some(OneOf::Left(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        semantic_tokens_provider: Some(SemanticTokensServerCapabilities::SemanticTokensOptions(
+            SemanticTokensOptions {
+                legend: semantic_tokens::legend(),
+                range: Some(true),
+                full: Some(SemanticTokensFullOptions::Bool(true)),
+                ..Default::default()
+            },
+        )),
+        ..Default::default()
+    })
+    .unwrap();
+
+    let initialization_params = connection.initialize(server_capabilities).unwrap();
+    eprintln!("server initialized");
+
+    main_loop(connection, initialization_params)?;
+
+    io_threads.join()?;
+    eprintln!("shutting down server");
+
+    Ok(())
+}
+
+fn main_loop(
+    connection: Connection,
+    params: serde_json::Value,
+) -> Result<(), Box<dyn Error + Sync + Send>> {
+    let params: InitializeParams = serde_json::from_value(params).unwrap();
+    eprintln!("starting example main loop");
+
+    let mem = IscMem::new();
+    let log = IscLog::new(&mem);
+
+    let parser = IscParser::new(&mem, &log).unwrap();
+
+    #[allow(deprecated)]
+    let folders = match (params.workspace_folders, params.root_uri) {
+        (Some(folders), _) => folders.iter().map(|f| uri_to_path(&f.uri)).collect(),
+        (None, Some(root)) => vec![uri_to_path(&root)],
+        (None, None) => vec![],
+    };
+    // settings sent by the client take precedence over the .bls.toml
+    let mut settings = params.initialization_options.unwrap_or_default();
+    let mut config = load_config(&connection, &folders, &settings);
+
+    let mut index = WorkspaceIndex::new(folders.clone());
+    index.rebuild(&parser, &config);
+
+    let mut documents = Documents::default();
+    let mut consistency: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+    let mut heads = HeadVersions::default();
+    for msg in &connection.receiver {
+        match msg {
+            Message::Notification(req) => match req.method.as_str() {
+                DidOpenTextDocument::METHOD => {
+                    let params = notification::<DidOpenTextDocument>(req)
+                        .expect("failed to parse notification");
+
+                    let doc = params.text_document;
+                    documents.open(doc.uri, &doc.language_id, doc.text, doc.version);
+                }
+                DidChangeTextDocument::METHOD => {
+                    let params = notification::<DidChangeTextDocument>(req)
+                        .expect("failed to parse notification");
+
+                    // with full sync the last change holds the whole document
+                    if let Some(change) = params.content_changes.into_iter().last() {
+                        documents.change(
+                            &params.text_document.uri,
+                            change.text,
+                            params.text_document.version,
+                        );
+                    }
+                }
+                DidCloseTextDocument::METHOD => {
+                    let params = notification::<DidCloseTextDocument>(req)
+                        .expect("failed to parse notification");
+
+                    documents.close(&params.text_document.uri);
+                }
+                DidChangeConfiguration::METHOD => {
+                    let params = notification::<DidChangeConfiguration>(req)
+                        .expect("failed to parse notification");
+
+                    settings = params.settings;
+                    config = load_config(&connection, &folders, &settings);
+                    index.rebuild(&parser, &config);
+                }
+                DidSaveTextDocument::METHOD => {
+                    eprintln!("notification: {req:?}");
+
+                    let params = notification::<DidSaveTextDocument>(req)
+                        .expect("failed to parse notification");
+
+                    let uri = params.text_document.uri;
+                    let path = uri_to_path(&uri);
+                    if path.file_name().is_some_and(|name| name == CONFIG_FILE) {
+                        config = load_config(&connection, &folders, &settings);
+                        index.rebuild(&parser, &config);
+                        continue;
+                    }
+                    let text = fs::read_to_string(&path).unwrap_or_default();
+
+                    let kind = match documents.get(&uri) {
+                        Some(doc) => doc.kind,
+                        None => DocumentKind::detect(&uri, None, &text),
+                    };
+                    match kind {
+                        DocumentKind::Zone => {
+                            let diagnostics = zone_file_diagnostics(
+                                &config,
+                                &mut heads,
+                                &path,
+                                &text,
+                                &consistency,
+                            );
+                            publish(&connection, uri, diagnostics);
+                        }
+                        DocumentKind::NamedConf => {
+                            let check = check_conf(&parser, &log, &mem, &config, &path);
+                            // BIND's messages about included files are shown
+                            // on the file that includes them
+                            let diagnostics = apply_suppressions(check.diagnostics, &path, &text)
+                                .into_iter()
+                                .filter(|d| d.source == "bind" || d.file == path)
+                                .collect();
+                            publish(&connection, uri, config.apply_severities(diagnostics));
+
+                            let checks = match &check.obj {
+                                Some(obj) if check.valid => {
+                                    check_all_zones(obj.root(), &check.resolver, false)
+                                }
+                                _ => vec![],
+                            };
+                            let previous = std::mem::take(&mut consistency);
+                            consistency = consistency_diagnostics(&checks)
+                                .into_iter()
+                                .map(|(file, d)| (fs::canonicalize(&file).unwrap_or(file), d))
+                                .collect();
+
+                            // refresh zone files that gained or lost mismatches
+                            let files = previous.keys().chain(consistency.keys());
+                            for file in files.collect::<HashSet<_>>() {
+                                let (Some(uri), Ok(text)) =
+                                    (path_to_uri(file), fs::read_to_string(file))
+                                else {
+                                    continue;
+                                };
+                                let diagnostics = zone_file_diagnostics(
+                                    &config,
+                                    &mut heads,
+                                    file,
+                                    &text,
+                                    &consistency,
+                                );
+                                publish(&connection, uri, diagnostics);
+                            }
+                        }
+                    }
+
+                    if kind == DocumentKind::NamedConf {
+                        index.rebuild(&parser, &config);
+                    }
+                }
+                _ => {
+                    eprintln!("received an unknown notification method: {}", req.method);
+                }
+            },
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+
+                match req.method.as_str() {
+                    DocumentDiagnosticRequest::METHOD => {
+                        let (_, params) = request::<DocumentDiagnosticRequest>(req)
+                            .expect("failed to parse request");
+                        eprintln!("got DocumentDiagnostic request: {params:?}");
+                    }
+                    CodeActionRequest::METHOD => {
+                        let (id, params) =
+                            request::<CodeActionRequest>(req).expect("failed to parse request");
+
+                        let uri = params.text_document.uri;
+                        let diagnostics = &params.context.diagnostics;
+                        let actions = match documents.get(&uri) {
+                            Some(doc) if doc.kind == DocumentKind::NamedConf => {
+                                code_actions(&uri, &doc.text, diagnostics)
+                            }
+                            Some(doc) => serial_actions(&uri, &doc.text, diagnostics),
+                            None => vec![],
+                        };
+                        let response = Response::new_ok(id, actions);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    DocumentSymbolRequest::METHOD => {
+                        let (id, params) =
+                            request::<DocumentSymbolRequest>(req).expect("failed to parse request");
+
+                        let uri = params.text_document.uri;
+                        let symbols = match documents.get(&uri) {
+                            Some(doc) if doc.kind == DocumentKind::Zone => {
+                                let path = uri_to_path(&uri);
+                                let origin = zone_origin(&path, &doc.text);
+                                let resolver = index
+                                    .zone_resolver(&path)
+                                    .cloned()
+                                    .unwrap_or_else(|| config.resolver(&path));
+                                zone_symbols(&doc.text, origin.as_deref(), &resolver)
+                            }
+                            _ => vec![],
+                        };
+                        let response =
+                            Response::new_ok(id, DocumentSymbolResponse::Nested(symbols));
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    GotoDefinition::METHOD => {
+                        let (id, params) =
+                            request::<GotoDefinition>(req).expect("failed to parse request");
+
+                        let params = params.text_document_position_params;
+                        let uri = params.text_document.uri;
+                        let location = match documents.get(&uri) {
+                            Some(doc) if doc.kind == DocumentKind::NamedConf => {
+                                let resolver =
+                                    config.resolver(&uri_to_path(&uri)).with_text(&doc.text);
+                                path_definition(&doc.text, params.position, &resolver)
+                            }
+                            _ => None,
+                        };
+                        let response = Response::new_ok(id, location);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    Formatting::METHOD => {
+                        let (id, params) =
+                            request::<Formatting>(req).expect("failed to parse request");
+
+                        let edits = match documents.get(&params.text_document.uri) {
+                            Some(doc) if doc.kind == DocumentKind::NamedConf => {
+                                conf_formatting(&doc.text, &params.options, &config.format)
+                            }
+                            _ => vec![],
+                        };
+                        let response = Response::new_ok(id, edits);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    FoldingRangeRequest::METHOD => {
+                        let (id, params) =
+                            request::<FoldingRangeRequest>(req).expect("failed to parse request");
+
+                        let ranges = match documents.get(&params.text_document.uri) {
+                            Some(doc) => folding_ranges(doc.kind, &doc.text),
+                            None => vec![],
+                        };
+                        let response = Response::new_ok(id, ranges);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    SemanticTokensFullRequest::METHOD => {
+                        let (id, params) = request::<SemanticTokensFullRequest>(req)
+                            .expect("failed to parse request");
+
+                        let tokens = match documents.get(&params.text_document.uri) {
+                            Some(doc) => semantic_tokens(doc.kind, &doc.text, None),
+                            None => SemanticTokens::default(),
+                        };
+                        let response = Response::new_ok(id, tokens);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    SemanticTokensRangeRequest::METHOD => {
+                        let (id, params) = request::<SemanticTokensRangeRequest>(req)
+                            .expect("failed to parse request");
+
+                        let tokens = match documents.get(&params.text_document.uri) {
+                            Some(doc) => semantic_tokens(doc.kind, &doc.text, Some(params.range)),
+                            None => SemanticTokens::default(),
+                        };
+                        let response = Response::new_ok(id, tokens);
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    WorkspaceSymbolRequest::METHOD => {
+                        let (id, params) = request::<WorkspaceSymbolRequest>(req)
+                            .expect("failed to parse request");
+
+                        let symbols = index.query(&params.query);
+                        let response = Response::new_ok(id, WorkspaceSymbolResponse::Flat(symbols));
+
+                        if let Err(err) = connection.sender.send(Message::Response(response)) {
+                            eprintln!("failed to send response: {}", err);
+                        }
+                    }
+                    _ => {
+                        eprintln!("received an unknown request method: {}", req.method);
+                    }
+                }
+            }
+            Message::Response(resp) => {
+                eprintln!("got response: {resp:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The diagnostics of a saved zone file: the unchanged serial warning, what
+/// BIND reports when loading it, lints, and forward/reverse mismatches found
+/// from the configuration.
+fn zone_file_diagnostics(
+    config: &ProjectConfig,
+    heads: &mut HeadVersions,
+    path: &Path,
+    text: &str,
+    consistency: &HashMap<PathBuf, Vec<Diagnostic>>,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = serial_diagnostics(heads, path, text);
+    diagnostics.extend(check_zone_file(
+        path,
+        text,
+        &config.zone,
+        &config.lint_options(),
+    ));
+
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if let Some(mismatches) = consistency.get(&canonical) {
+        // found through the configuration, which may spell the path
+        // differently
+        diagnostics.extend(mismatches.iter().cloned().map(|mut mismatch| {
+            mismatch.file = path.to_path_buf();
+            mismatch
+        }));
+    }
+
+    config.apply_severities(apply_suppressions(diagnostics, path, text))
+}
+
+/// The project configuration from the `.bls.toml` of the first workspace
+/// folder, with the client's settings applied over it. Broken settings are
+/// shown to the user and leave the `.bls.toml` as it is.
+fn load_config(
+    connection: &Connection,
+    folders: &[PathBuf],
+    settings: &serde_json::Value,
+) -> ProjectConfig {
+    let Some(folder) = folders.first() else {
+        return ProjectConfig::default();
+    };
+
+    let err = match ProjectConfig::discover_with_settings(folder, settings) {
+        Ok(config) => return config,
+        Err(err) => err,
+    };
+    match ProjectConfig::discover(folder) {
+        Ok(config) => {
+            show_message(connection, format!("invalid bls settings: {}", err));
+            config
+        }
+        Err(err) => {
+            show_message(connection, format!("invalid configuration: {}", err));
+            ProjectConfig::default()
+        }
+    }
+}
+
+fn show_message(connection: &Connection, message: String) {
+    eprintln!("{}", message);
+
+    let notification = Notification::new(
+        ShowMessage::METHOD.to_string(),
+        ShowMessageParams {
+            typ: MessageType::ERROR,
+            message,
+        },
+    );
+
+    if let Err(err) = connection.sender.send(Message::Notification(notification)) {
+        eprintln!("failed to send notification: {}", err);
+    }
+}
+
+fn publish(connection: &Connection, uri: Uri, diagnostics: Vec<Diagnostic>) {
+    let notification = Notification::new(
+        PublishDiagnostics::METHOD.to_string(),
+        PublishDiagnosticsParams {
+            uri,
+            diagnostics: diagnostics.iter().map(Diagnostic::to_lsp).collect(),
+            version: None,
+        },
+    );
+
+    if let Err(err) = connection.sender.send(Message::Notification(notification)) {
+        eprintln!("failed to send notification: {}", err);
+    }
+}
+
+fn request<R>(req: Request) -> Result<(RequestId, R::Params), ExtractError<Request>>
+where
+    R: lsp_types::request::Request,
+    R::Params: serde::de::DeserializeOwned,
+{
+    req.extract(R::METHOD)
+}
+
+fn notification<R>(req: Notification) -> Result<R::Params, ExtractError<Notification>>
+where
+    R: lsp_types::notification::Notification,
+    R::Params: serde::de::DeserializeOwned,
+{
+    req.extract(R::METHOD)
+}
@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::{env, process};
+
+mod check;
+mod checkconf;
+mod lsp;
+mod zone;
+
+const USAGE: &str = "usage: bls [lsp]
+       bls check [-z] [--secondaries] [-t DIRECTORY] [--security]
+                 [--format text|json|sarif] FILE...
+       bls checkconf [-z] [--secondaries] [-t DIRECTORY] FILE
+       bls zone export [--format json] ZONE FILE
+       bls zone import [FILE]
+       bls zone diff [--format text|json|ixfr|nsupdate] --origin ZONE OLD NEW
+       bls zone convert [--from text|raw] [--to text|raw] [--style relative|explicitttl|full]
+                        [-o OUTPUT] ZONE INPUT
+       bls zone canonical [-o OUTPUT] ZONE INPUT
+       bls zone stats [--format table|json] ZONE FILE";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    if let Err(err) = run(&args) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args.first().map(String::as_str) {
+        // editors commonly pass transport flags such as --stdio
+        None | Some("lsp") => lsp::run().map_err(|err| err as Box<dyn Error>),
+        Some(flag) if flag.starts_with("--") => lsp::run().map_err(|err| err as Box<dyn Error>),
+        Some("check") => check::run(&args[1..]),
+        Some("checkconf") => checkconf::run(&args[1..]),
+        Some("zone") => zone::run(&args[1..]),
+        Some(_) => Err(USAGE.into()),
+    }
+}
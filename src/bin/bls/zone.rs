@@ -0,0 +1,212 @@
+use std::error::Error;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use bind_parser::bind::zone::{DnsZone, DumpStyle, MasterFormat, ZoneOptions};
+use bind_parser::dns::diff::ZoneDiff;
+use bind_parser::dns::export::ZoneExport;
+use bind_parser::dns::stats::ZoneStats;
+
+use crate::USAGE;
+
+pub fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    match args.first().map(String::as_str) {
+        Some("export") => export(&args[1..]),
+        Some("import") => import(&args[1..]),
+        Some("diff") => diff(&args[1..]),
+        Some("convert") => convert(&args[1..]),
+        Some("canonical") => canonical(&args[1..]),
+        Some("stats") => stats(&args[1..]),
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn export(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut format = "json";
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().ok_or(USAGE)?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [origin, path] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    if format != "json" {
+        return Err(format!("unsupported format: {}", format).into());
+    }
+
+    let text = fs::read_to_string(path)?;
+    let zone = DnsZone::load(origin, &text)?;
+    let export = ZoneExport::new(origin, zone.records()?);
+    println!("{}", export.to_json()?);
+
+    Ok(())
+}
+
+fn import(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let json = match args {
+        [] => {
+            let mut json = String::new();
+            io::stdin().read_to_string(&mut json)?;
+            json
+        }
+        [path] => fs::read_to_string(path)?,
+        _ => return Err(USAGE.into()),
+    };
+
+    let export = ZoneExport::from_json(&json)?;
+    let text = export.to_master_file();
+
+    // only print zones BIND accepts
+    DnsZone::load(&export.origin, &text)?;
+    print!("{}", text);
+
+    Ok(())
+}
+
+fn diff(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut origin = None;
+    let mut format = "text";
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--origin" => origin = Some(args.next().ok_or(USAGE)?),
+            "--format" => format = args.next().ok_or(USAGE)?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let ([old_path, new_path], Some(origin)) = (&positional[..], origin) else {
+        return Err(USAGE.into());
+    };
+
+    let old = DnsZone::load(origin, &fs::read_to_string(old_path)?)?;
+    let new = DnsZone::load(origin, &fs::read_to_string(new_path)?)?;
+
+    match format {
+        "text" | "json" => {
+            let diff = ZoneDiff::new(origin, &old.records()?, &new.records()?);
+            match format {
+                "text" => print!("{}", diff.to_text()),
+                _ => println!("{}", serde_json::to_string_pretty(&diff)?),
+            }
+        }
+        "ixfr" | "nsupdate" => {
+            let Some(ixfr) = old.ixfr(origin, &new)? else {
+                return Ok(());
+            };
+            if ixfr.serial_bumped {
+                eprintln!("{}: serial not increased, using {}", origin, ixfr.new_soa);
+            }
+            match format {
+                "ixfr" => print!("{}", ixfr.to_text()),
+                _ => print!("{}", ixfr.to_nsupdate()),
+            }
+        }
+        _ => return Err(format!("unsupported format: {}", format).into()),
+    }
+
+    Ok(())
+}
+
+fn convert(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut from = MasterFormat::Text;
+    let mut to = MasterFormat::Text;
+    let mut style = DumpStyle::Relative;
+    let mut output = None;
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" | "-f" => from = master_format(args.next().ok_or(USAGE)?)?,
+            "--to" | "-F" => to = master_format(args.next().ok_or(USAGE)?)?,
+            "--style" | "-s" => {
+                let name = args.next().ok_or(USAGE)?;
+                style = DumpStyle::parse(name).ok_or(format!("unsupported style: {}", name))?;
+            }
+            "--output" | "-o" => output = Some(args.next().ok_or(USAGE)?),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [origin, input] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    let output = output.filter(|o| *o != "-").map(Path::new);
+
+    let zone = DnsZone::load_file(origin, Path::new(input), from, &ZoneOptions::default())?;
+    zone.dump(output, to, style)
+}
+
+/// Rewrites a text zone in canonical form, for normalizing zones before
+/// they are committed or compared.
+fn canonical(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut output = None;
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output" | "-o" => output = Some(args.next().ok_or(USAGE)?),
+            _ => positional.push(arg),
+        }
+    }
+
+    let [origin, input] = positional[..] else {
+        return Err(USAGE.into());
+    };
+    let output = output.filter(|o| *o != "-").map(Path::new);
+
+    let zone = DnsZone::load_file(
+        origin,
+        Path::new(input),
+        MasterFormat::Text,
+        &ZoneOptions::default(),
+    )?;
+    zone.dump(output, MasterFormat::Text, DumpStyle::Full)
+}
+
+fn master_format(name: &str) -> Result<MasterFormat, Box<dyn Error>> {
+    match (MasterFormat::parse(name), name) {
+        (Some(format), _) => Ok(format),
+        (None, "map") => Err("the map format is not supported by BIND 9.20".into()),
+        (None, _) => Err(format!("unsupported format: {}", name).into()),
+    }
+}
+
+fn stats(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut format = "table";
+    let mut positional = vec![];
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = args.next().ok_or(USAGE)?,
+            _ => positional.push(arg),
+        }
+    }
+
+    let [origin, path] = positional[..] else {
+        return Err(USAGE.into());
+    };
+
+    let zone = DnsZone::load(origin, &fs::read_to_string(path)?)?;
+    let stats = ZoneStats::new(origin, &zone.records()?);
+
+    match format {
+        "table" => print!("{}", stats.to_table()),
+        "json" => println!("{}", serde_json::to_string_pretty(&stats)?),
+        _ => return Err(format!("unsupported format: {}", format).into()),
+    }
+
+    Ok(())
+}
@@ -5,10 +5,14 @@ use std::{
     ptr::null_mut,
 };
 
-use crate::{fclose, fmemopen, isc_buffer__bindgen_ty_1, isc_buffer_t, FILE, ISC_BUFFER_MAGIC};
+use crate::{
+    fclose, fflush, fmemopen, isc_buffer__bindgen_ty_1, isc_buffer_t, rewind, FILE,
+    ISC_BUFFER_MAGIC,
+};
 
 pub struct IscBuffer {
     isc_buffer: *mut isc_buffer_t,
+    _storage: Vec<u8>,
 }
 
 impl Drop for IscBuffer {
@@ -37,7 +41,53 @@ impl IscBuffer {
             mctx: null_mut(),
         }));
 
-        IscBuffer { isc_buffer }
+        IscBuffer {
+            isc_buffer,
+            _storage: vec![],
+        }
+    }
+
+    /// An empty buffer with room for `len` bytes, for BIND to write into.
+    pub fn with_capacity(len: usize) -> Self {
+        let mut storage = vec![0u8; len];
+        let isc_buffer = Box::into_raw(Box::new(isc_buffer_t {
+            base: storage.as_mut_ptr() as *mut c_void,
+            length: len as u32,
+            used: 0,
+            link: isc_buffer__bindgen_ty_1 {
+                prev: -1_isize as *mut isc_buffer_t,
+                next: -1_isize as *mut isc_buffer_t,
+            },
+            magic: ISC_BUFFER_MAGIC,
+            current: 0,
+            active: 0,
+            extra: 0,
+            dynamic: false,
+            mctx: null_mut(),
+        }));
+
+        IscBuffer {
+            isc_buffer,
+            _storage: storage,
+        }
+    }
+
+    /// The used region of the buffer.
+    pub fn as_str(&self) -> &str {
+        unsafe {
+            let buffer = &*self.isc_buffer;
+            let bytes = std::slice::from_raw_parts(buffer.base as *const u8, buffer.used as usize);
+            std::str::from_utf8(bytes).expect("failed to convert buffer to str")
+        }
+    }
+
+    pub fn clear(&self) {
+        unsafe {
+            let buffer = &mut *self.isc_buffer;
+            buffer.used = 0;
+            buffer.current = 0;
+            buffer.active = 0;
+        }
     }
 
     pub fn as_ptr(&self) -> *mut isc_buffer_t {
@@ -48,6 +98,7 @@ impl IscBuffer {
 pub struct MemFile {
     file: *mut FILE,
     ptr: *mut c_void,
+    len: usize,
 }
 
 impl MemFile {
@@ -55,7 +106,7 @@ impl MemFile {
         let ptr = Box::into_raw(Box::new([0u8; N])) as *mut c_void;
         let file = unsafe { fmemopen(ptr, N, "w+\0".as_ptr() as *const i8) };
 
-        MemFile { file, ptr }
+        MemFile { file, ptr, len: N }
     }
 
     pub fn from_str(s: &str) -> Self {
@@ -65,12 +116,22 @@ impl MemFile {
 
         let file = unsafe { fmemopen(ptr, len, "r\0".as_ptr() as *const i8) };
 
-        MemFile { file, ptr }
+        MemFile { file, ptr, len }
     }
 
     pub fn as_ptr(&self) -> *mut FILE {
         self.file
     }
+
+    /// Discards everything written so far and starts over at the beginning
+    /// of the buffer.
+    pub fn clear(&self) {
+        unsafe {
+            fflush(self.file);
+            rewind(self.file);
+            std::ptr::write_bytes(self.ptr as *mut u8, 0, self.len);
+        }
+    }
 }
 
 impl Display for MemFile {
@@ -0,0 +1,316 @@
+use std::{
+    error::Error,
+    ffi::CString,
+    fs, io,
+    mem::ManuallyDrop,
+    os::fd::FromRawFd,
+    path::Path,
+    ptr::{null, null_mut},
+};
+
+use crate::{
+    dns__db_allrdatasets, dns__db_detachnode, dns__dbiterator_current, dns__rdataset_disassociate,
+    dns__rdatasetiter_current, dns__rdatasetiter_destroy, dns_db_closeversion,
+    dns_db_createiterator, dns_db_currentversion, dns_db_detach, dns_db_diffx, dns_db_t,
+    dns_dbiterator_destroy, dns_dbiterator_first, dns_dbiterator_next, dns_dbiterator_pause,
+    dns_dbversion_t, dns_diff_clear, dns_diff_init, dns_diff_t, dns_diffop_t_DNS_DIFFOP_ADD,
+    dns_diffop_t_DNS_DIFFOP_DEL, dns_fixedname_initname, dns_master_dump, dns_master_dumptostream,
+    dns_master_initrawheader, dns_masterrawheader_t, dns_name_t, dns_name_totext, dns_rdata_init,
+    dns_rdata_t, dns_rdata_totext, dns_rdataclass_totext, dns_rdataset_current, dns_rdataset_first,
+    dns_rdataset_init, dns_rdataset_next, dns_rdataset_t, dns_rdatasetiter_first,
+    dns_rdatasetiter_next, dns_rdatatype_totext, dns_zone_getdb, dns_zone_t, fclose, fflush,
+    fileno, isc_buffer_t, isc_result_ISC_R_NOMORE, isc_result_ISC_R_SUCCESS, isc_result_t, rewind,
+    tmpfile,
+};
+
+use crate::dns::{
+    diff::DiffOp,
+    record::{RData, Record},
+};
+
+use super::{
+    buffer::IscBuffer,
+    mem::IscMem,
+    zone::{DumpStyle, MasterFormat},
+};
+
+/// Large enough for the presentation format of any single rdata.
+const TEXT_BUFFER_SIZE: usize = 256 * 1024;
+
+/// The current version of a loaded zone's database.
+pub struct DnsDb {
+    db: *mut dns_db_t,
+    version: *mut dns_dbversion_t,
+}
+
+impl DnsDb {
+    pub fn from_zone(zone: *mut dns_zone_t) -> Result<Self, Box<dyn Error>> {
+        let mut db: *mut dns_db_t = null_mut();
+        let mut version: *mut dns_dbversion_t = null_mut();
+
+        unsafe {
+            let ret = dns_zone_getdb(zone, &mut db);
+            if ret != isc_result_ISC_R_SUCCESS {
+                return Err("dns_zone_getdb failed".into());
+            }
+            dns_db_currentversion(db, &mut version);
+        }
+
+        Ok(DnsDb { db, version })
+    }
+
+    pub fn as_ptr(&self) -> *mut dns_db_t {
+        self.db
+    }
+
+    pub fn version(&self) -> *mut dns_dbversion_t {
+        self.version
+    }
+
+    /// Every record in the database, in the database's order of owner names.
+    pub fn records(&self) -> Result<Vec<Record>, Box<dyn Error>> {
+        let buffer = IscBuffer::with_capacity(TEXT_BUFFER_SIZE);
+        let mut records = vec![];
+
+        unsafe {
+            let mut iter = null_mut();
+            let ret = dns_db_createiterator(self.db, 0, &mut iter);
+            if ret != isc_result_ISC_R_SUCCESS {
+                return Err("dns_db_createiterator failed".into());
+            }
+
+            let mut fixname = std::mem::zeroed();
+            let name = dns_fixedname_initname(&mut fixname);
+
+            let mut ret = dns_dbiterator_first(iter);
+            while ret == isc_result_ISC_R_SUCCESS {
+                let mut node = null_mut();
+                ret = dns__dbiterator_current(iter, &mut node, name);
+                if ret != isc_result_ISC_R_SUCCESS {
+                    break;
+                }
+                dns_dbiterator_pause(iter);
+
+                let mut rdsiter = null_mut();
+                ret = dns__db_allrdatasets(self.db, node, self.version, 0, 0, &mut rdsiter);
+                if ret == isc_result_ISC_R_SUCCESS {
+                    let mut next = dns_rdatasetiter_first(rdsiter);
+                    while next == isc_result_ISC_R_SUCCESS {
+                        let mut rdataset: dns_rdataset_t = std::mem::zeroed();
+                        dns_rdataset_init(&mut rdataset);
+                        dns__rdatasetiter_current(rdsiter, &mut rdataset);
+                        records.extend(rdataset_records(&buffer, name, &mut rdataset));
+                        dns__rdataset_disassociate(&mut rdataset);
+
+                        next = dns_rdatasetiter_next(rdsiter);
+                    }
+                    dns__rdatasetiter_destroy(&mut rdsiter);
+                }
+                dns__db_detachnode(self.db, &mut node);
+
+                if ret != isc_result_ISC_R_SUCCESS {
+                    break;
+                }
+                ret = dns_dbiterator_next(iter);
+            }
+            dns_dbiterator_destroy(&mut iter);
+
+            if ret != isc_result_ISC_R_NOMORE {
+                return Err("failed to iterate zone database".into());
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// The changes from this database to `newer`, as computed by
+    /// `dns_db_diffx`.
+    pub fn diff(
+        &self,
+        mem: &IscMem,
+        newer: &DnsDb,
+    ) -> Result<Vec<(DiffOp, Record)>, Box<dyn Error>> {
+        let buffer = IscBuffer::with_capacity(TEXT_BUFFER_SIZE);
+        let mut tuples = vec![];
+
+        unsafe {
+            let mut diff: dns_diff_t = std::mem::zeroed();
+            dns_diff_init(mem.as_ptr(), &mut diff);
+
+            let ret = dns_db_diffx(
+                &mut diff,
+                self.db,
+                self.version,
+                newer.db,
+                newer.version,
+                null(),
+            );
+            if ret == isc_result_ISC_R_SUCCESS {
+                let mut tuple = diff.tuples.head;
+                while !tuple.is_null() {
+                    let op = match (*tuple).op {
+                        op if op == dns_diffop_t_DNS_DIFFOP_ADD => Some(DiffOp::Add),
+                        op if op == dns_diffop_t_DNS_DIFFOP_DEL => Some(DiffOp::Delete),
+                        _ => None,
+                    };
+                    if let Some(op) = op {
+                        let record =
+                            record(&buffer, &(*tuple).name, (*tuple).ttl, &mut (*tuple).rdata);
+                        tuples.push((op, record));
+                    }
+
+                    tuple = (*tuple).link.next;
+                }
+            }
+            dns_diff_clear(&mut diff);
+
+            if ret != isc_result_ISC_R_SUCCESS {
+                return Err("dns_db_diffx failed".into());
+            }
+        }
+
+        Ok(tuples)
+    }
+
+    /// Writes the database as a master file in `format` and `style` to
+    /// `output`, or to standard output.
+    pub fn dump(
+        &self,
+        mem: &IscMem,
+        output: Option<&Path>,
+        format: MasterFormat,
+        style: DumpStyle,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(path) = output else {
+            return self.dump_to_stdout(mem, format, style);
+        };
+        let file = CString::new(path.to_str().ok_or("invalid path")?)?;
+
+        let ret = unsafe {
+            let mut header: dns_masterrawheader_t = std::mem::zeroed();
+            dns_master_initrawheader(&mut header);
+
+            dns_master_dump(
+                mem.as_ptr(),
+                self.db,
+                self.version,
+                style.as_ptr(),
+                file.as_ptr(),
+                format.as_raw(),
+                &mut header,
+            )
+        };
+        if ret != isc_result_ISC_R_SUCCESS {
+            return Err("dns_master_dump failed".into());
+        }
+
+        Ok(())
+    }
+
+    /// Dumps to an anonymous temporary file and copies it, so the output
+    /// goes through Rust's buffered stdout rather than a second C stream on
+    /// it.
+    fn dump_to_stdout(
+        &self,
+        mem: &IscMem,
+        format: MasterFormat,
+        style: DumpStyle,
+    ) -> Result<(), Box<dyn Error>> {
+        // unlinked on creation, so no other process can open or replace it
+        let stream = unsafe { tmpfile() };
+        if stream.is_null() {
+            return Err(io::Error::last_os_error().into());
+        }
+
+        let ret = unsafe {
+            let mut header: dns_masterrawheader_t = std::mem::zeroed();
+            dns_master_initrawheader(&mut header);
+
+            let ret = dns_master_dumptostream(
+                mem.as_ptr(),
+                self.db,
+                self.version,
+                style.as_ptr(),
+                format.as_raw(),
+                &mut header,
+                stream,
+            );
+            fflush(stream);
+            rewind(stream);
+            ret
+        };
+
+        let copied: Result<(), Box<dyn Error>> = if ret == isc_result_ISC_R_SUCCESS {
+            // the descriptor stays owned by the stream, which closes it
+            let mut file = ManuallyDrop::new(unsafe { fs::File::from_raw_fd(fileno(stream)) });
+            io::copy(&mut *file, &mut io::stdout().lock())
+                .map(|_| ())
+                .map_err(Into::into)
+        } else {
+            Err("dns_master_dumptostream failed".into())
+        };
+        unsafe { fclose(stream) };
+
+        copied
+    }
+}
+
+impl Drop for DnsDb {
+    fn drop(&mut self) {
+        unsafe {
+            dns_db_closeversion(self.db, &mut self.version, false);
+            dns_db_detach(&mut self.db);
+        }
+    }
+}
+
+unsafe fn rdataset_records(
+    buffer: &IscBuffer,
+    name: *const dns_name_t,
+    rdataset: &mut dns_rdataset_t,
+) -> Vec<Record> {
+    let mut records = vec![];
+
+    let mut ret = dns_rdataset_first(rdataset);
+    while ret == isc_result_ISC_R_SUCCESS {
+        let mut rdata: dns_rdata_t = std::mem::zeroed();
+        dns_rdata_init(&mut rdata);
+        dns_rdataset_current(rdataset, &mut rdata);
+        records.push(record(buffer, name, rdataset.ttl, &mut rdata));
+
+        ret = dns_rdataset_next(rdataset);
+    }
+
+    records
+}
+
+unsafe fn record(
+    buffer: &IscBuffer,
+    name: *const dns_name_t,
+    ttl: u32,
+    rdata: *mut dns_rdata_t,
+) -> Record {
+    let name = to_text(buffer, |target| dns_name_totext(name, 0, target));
+    let class = to_text(buffer, |target| {
+        dns_rdataclass_totext((*rdata).rdclass, target)
+    });
+    let rtype = to_text(buffer, |target| {
+        dns_rdatatype_totext((*rdata).type_, target)
+    });
+    let text = to_text(buffer, |target| dns_rdata_totext(rdata, null_mut(), target));
+
+    Record {
+        name,
+        ttl,
+        class,
+        data: RData::parse(&rtype, &text),
+    }
+}
+
+fn to_text(buffer: &IscBuffer, f: impl FnOnce(*mut isc_buffer_t) -> isc_result_t) -> String {
+    buffer.clear();
+    match f(buffer.as_ptr()) {
+        ret if ret == isc_result_ISC_R_SUCCESS => buffer.as_str().to_string(),
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,70 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    ffi::CStr,
+    ptr::addr_of,
+    sync::OnceLock,
+};
+
+use crate::{
+    cfg_clausedef_t, cfg_rep_list, cfg_rep_map, cfg_rep_t, cfg_rep_tuple, cfg_tuplefielddef_t,
+    cfg_type_namedconf, cfg_type_t,
+};
+
+/// Every clause name known to the `named.conf` grammar, at any nesting
+/// level, e.g. `options`, `allow-query` or `primaries`.
+pub fn clause_names() -> &'static BTreeSet<String> {
+    static NAMES: OnceLock<BTreeSet<String>> = OnceLock::new();
+
+    NAMES.get_or_init(|| {
+        let mut names = BTreeSet::new();
+        let mut seen = HashSet::new();
+        unsafe { walk(addr_of!(cfg_type_namedconf), &mut seen, &mut names) };
+        names
+    })
+}
+
+unsafe fn walk(
+    ty: *const cfg_type_t,
+    seen: &mut HashSet<*const cfg_type_t>,
+    names: &mut BTreeSet<String>,
+) {
+    if ty.is_null() || !seen.insert(ty) {
+        return;
+    }
+
+    let rep = (*ty).rep as *const cfg_rep_t;
+    if rep == addr_of!(cfg_rep_map) {
+        // a NULL terminated array of clause sets, each one terminated by
+        // a clause without a name
+        let mut sets = (*ty).of as *const *const cfg_clausedef_t;
+        while !(*sets).is_null() {
+            let mut clause = *sets;
+            while !(*clause).name.is_null() {
+                if let Ok(name) = CStr::from_ptr((*clause).name).to_str() {
+                    names.insert(name.to_string());
+                }
+                walk((*clause).type_, seen, names);
+                clause = clause.add(1);
+            }
+            sets = sets.add(1);
+        }
+    } else if rep == addr_of!(cfg_rep_tuple) {
+        let mut field = (*ty).of as *const cfg_tuplefielddef_t;
+        while !(*field).name.is_null() {
+            walk((*field).type_, seen, names);
+            field = field.add(1);
+        }
+    } else if rep == addr_of!(cfg_rep_list) {
+        walk((*ty).of as *const cfg_type_t, seen, names);
+    }
+}
+
+#[test]
+fn test_clause_names() {
+    let names = clause_names();
+
+    assert!(names.contains("options"));
+    assert!(names.contains("allow-query"));
+    assert!(names.contains("primaries"));
+    assert!(names.contains("dnssec-policy"));
+}
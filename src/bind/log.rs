@@ -5,10 +5,11 @@ use std::{
 };
 
 use crate::{
-    cfg_log_init, dns_log_init, dns_log_setcontext, isc_log, isc_log_create, isc_log_createchannel,
-    isc_log_destroy, isc_log_registercategories, isc_log_setcontext, isc_log_t, isc_log_usechannel,
-    isc_logcategory_t, isc_logconfig_t, isc_logdestination_t, isc_result_ISC_R_SUCCESS,
-    ns_log_init, ISC_LOG_DYNAMIC, ISC_LOG_ROLLNEVER, ISC_LOG_TOFILEDESC,
+    cfg_log_init, dns_lctx, dns_log_init, dns_log_setcontext, isc_log, isc_log_create,
+    isc_log_createchannel, isc_log_destroy, isc_log_registercategories, isc_log_setcontext,
+    isc_log_t, isc_log_usechannel, isc_logcategory_t, isc_logconfig_t, isc_logdestination_t,
+    isc_result_ISC_R_SUCCESS, ns_log_init, ISC_LOG_DYNAMIC, ISC_LOG_PRINTLEVEL, ISC_LOG_ROLLNEVER,
+    ISC_LOG_TOFILEDESC,
 };
 
 use super::{buffer::MemFile, mem::IscMem};
@@ -54,13 +55,15 @@ impl IscLog {
             destination.file.name = std::ptr::null();
             destination.file.versions = ISC_LOG_ROLLNEVER;
             destination.file.maximum_size = 0;
+            // each line starts with its level, such as `warning: `, which
+            // is where diagnostics take their severity from
             isc_log_createchannel(
                 logconfig,
                 "stderr\0".as_ptr() as *const c_char,
                 ISC_LOG_TOFILEDESC,
                 ISC_LOG_DYNAMIC as c_int,
                 &destination,
-                0,
+                ISC_LOG_PRINTLEVEL,
             );
 
             isc_log_usechannel(
@@ -81,6 +84,46 @@ impl IscLog {
     pub fn as_ptr(&self) -> *mut isc_log {
         self.log
     }
+
+    pub fn clear(&self) {
+        self.file.clear();
+    }
+
+    /// Makes libisc and libdns log here. Every new `IscLog` takes over the
+    /// global context.
+    pub fn set_context(&self) {
+        set_context(self.log);
+    }
+}
+
+/// The global log context, handed back when dropped. Lets a log that is
+/// only needed for a while, like the one of a `DnsZone`, take over without
+/// leaving the context pointing at it once it is destroyed.
+pub(crate) struct SavedContext {
+    log: *mut isc_log_t,
+}
+
+impl SavedContext {
+    pub(crate) fn save() -> Self {
+        // libisc has no getter, and the libdns context is always set along
+        // with it
+        Self {
+            log: unsafe { dns_lctx },
+        }
+    }
+}
+
+impl Drop for SavedContext {
+    fn drop(&mut self) {
+        set_context(self.log);
+    }
+}
+
+fn set_context(log: *mut isc_log_t) {
+    unsafe {
+        isc_log_setcontext(log);
+        dns_log_setcontext(log);
+    }
 }
 
 impl Display for IscLog {
@@ -1,6 +1,8 @@
+pub mod grammar;
 pub mod log;
 pub mod mem;
 pub mod obj;
 pub mod parser;
 pub mod buffer;
+pub mod db;
 pub mod zone;
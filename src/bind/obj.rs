@@ -1,32 +1,97 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString},
+    net::{IpAddr, SocketAddr},
+    os::raw::c_char,
+    ptr::{addr_of, null},
+};
+
 use crate::{
-    cfg_obj_destroy, cfg_obj_t, isc_result_ISC_R_SUCCESS, isccfg_check_namedconf,
-    BIND_CHECK_ALGORITHMS, BIND_CHECK_PLUGINS,
+    cfg_list_first, cfg_list_next, cfg_listelt_value, cfg_map_get, cfg_map_getname,
+    cfg_obj_asboolean, cfg_obj_asduration, cfg_obj_asnetprefix, cfg_obj_assockaddr,
+    cfg_obj_asstring, cfg_obj_destroy, cfg_obj_file, cfg_obj_isboolean, cfg_obj_isduration,
+    cfg_obj_islist, cfg_obj_ismap, cfg_obj_isnetprefix, cfg_obj_issockaddr, cfg_obj_isstring,
+    cfg_obj_istuple, cfg_obj_istype, cfg_obj_isvoid, cfg_obj_line, cfg_obj_t, cfg_tuple_get,
+    cfg_type_keyref, isc_netaddr_format, isc_netaddr_fromsockaddr, isc_netaddr_t,
+    isc_result_ISC_R_SUCCESS, isc_sockaddr_getport, isccfg_check_namedconf, BIND_CHECK_ALGORITHMS,
+    BIND_CHECK_PLUGINS,
 };
 
+use serde::Deserialize;
+
 use super::{log::IscLog, mem::IscMem, parser::IscParser};
 
+/// The optional checks of `isccfg_check_namedconf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CheckFlags {
+    /// Load the configured plugins to check their parameters.
+    pub plugins: bool,
+    /// Check that DNSSEC algorithms are supported by the crypto library.
+    pub algorithms: bool,
+}
+
+impl Default for CheckFlags {
+    fn default() -> Self {
+        CheckFlags {
+            plugins: true,
+            algorithms: true,
+        }
+    }
+}
+
+impl CheckFlags {
+    fn bits(&self) -> u32 {
+        let mut bits = 0;
+        if self.plugins {
+            bits |= BIND_CHECK_PLUGINS;
+        }
+        if self.algorithms {
+            bits |= BIND_CHECK_ALGORITHMS;
+        }
+
+        bits
+    }
+}
+
 pub struct Obj<'a> {
     cfg_obj: *mut cfg_obj_t,
     parser: &'a IscParser,
+    /// The files copies of included files stand for, by the path of the
+    /// copy, as made by [`crate::path::IncludedFiles`].
+    originals: HashMap<String, String>,
 }
 
 impl<'a> Obj<'a> {
     pub fn new(parser: &'a IscParser, cfg_obj: *mut cfg_obj_t) -> Self {
-        Obj { cfg_obj, parser }
+        Obj {
+            cfg_obj,
+            parser,
+            originals: HashMap::new(),
+        }
+    }
+
+    /// Reports the objects of copies of included files as in the originals.
+    pub fn with_originals(mut self, originals: HashMap<String, String>) -> Self {
+        self.originals = originals;
+        self
     }
 
     pub fn check(&self, log: &IscLog, mem: &IscMem) -> bool {
+        self.check_with(log, mem, CheckFlags::default())
+    }
+
+    pub fn check_with(&self, log: &IscLog, mem: &IscMem, flags: CheckFlags) -> bool {
         let ret = unsafe {
-            isccfg_check_namedconf(
-                self.cfg_obj,
-                BIND_CHECK_PLUGINS | BIND_CHECK_ALGORITHMS,
-                log.as_ptr(),
-                mem.as_ptr(),
-            )
+            isccfg_check_namedconf(self.cfg_obj, flags.bits(), log.as_ptr(), mem.as_ptr())
         };
 
         ret == isc_result_ISC_R_SUCCESS
     }
+
+    pub fn root(&self) -> ObjRef<'_> {
+        ObjRef::new(self.cfg_obj, &self.originals)
+    }
 }
 
 impl<'a> Drop for Obj<'a> {
@@ -36,3 +101,192 @@ impl<'a> Drop for Obj<'a> {
         }
     }
 }
+
+/// A borrowed node of a parsed configuration tree, valid as long as the
+/// owning [`Obj`] is alive.
+#[derive(Clone, Copy)]
+pub struct ObjRef<'a> {
+    cfg_obj: *const cfg_obj_t,
+    originals: &'a HashMap<String, String>,
+}
+
+impl<'a> ObjRef<'a> {
+    fn new(cfg_obj: *const cfg_obj_t, originals: &'a HashMap<String, String>) -> Self {
+        ObjRef { cfg_obj, originals }
+    }
+
+    /// A node of the same tree as this one.
+    fn from_ptr(&self, cfg_obj: *const cfg_obj_t) -> Option<Self> {
+        if cfg_obj.is_null() {
+            None
+        } else {
+            Some(ObjRef::new(cfg_obj, self.originals))
+        }
+    }
+
+    /// Looks up a clause of a map, e.g. `zone` in the top-level config or
+    /// `directory` in `options`.
+    pub fn map_get(&self, name: &str) -> Option<ObjRef<'a>> {
+        if !self.is_map() {
+            return None;
+        }
+
+        let name = CString::new(name).ok()?;
+        let mut obj = null();
+        let ret = unsafe { cfg_map_get(self.cfg_obj, name.as_ptr(), &mut obj) };
+        if ret != isc_result_ISC_R_SUCCESS {
+            return None;
+        }
+
+        self.from_ptr(obj)
+    }
+
+    /// Returns the name of a named map such as `key "name" { ... }`.
+    pub fn map_name(&self) -> Option<ObjRef<'a>> {
+        if !self.is_map() {
+            return None;
+        }
+
+        self.from_ptr(unsafe { cfg_map_getname(self.cfg_obj) })
+    }
+
+    pub fn tuple_get(&self, name: &str) -> Option<ObjRef<'a>> {
+        if !self.is_tuple() {
+            return None;
+        }
+
+        let name = CString::new(name).ok()?;
+        self.from_ptr(unsafe { cfg_tuple_get(self.cfg_obj, name.as_ptr()) })
+    }
+
+    /// Returns the elements of a list, or an empty vector for any other
+    /// kind of object.
+    pub fn list(&self) -> Vec<ObjRef<'a>> {
+        let mut items = vec![];
+        if !self.is_list() {
+            return items;
+        }
+
+        unsafe {
+            let mut elt = cfg_list_first(self.cfg_obj);
+            while !elt.is_null() {
+                if let Some(value) = self.from_ptr(cfg_listelt_value(elt)) {
+                    items.push(value);
+                }
+                elt = cfg_list_next(elt);
+            }
+        }
+
+        items
+    }
+
+    pub fn as_str(&self) -> Option<&'a str> {
+        if !unsafe { cfg_obj_isstring(self.cfg_obj) } {
+            return None;
+        }
+
+        let s = unsafe { CStr::from_ptr(cfg_obj_asstring(self.cfg_obj)) };
+        s.to_str().ok()
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        if !unsafe { cfg_obj_isboolean(self.cfg_obj) } {
+            return None;
+        }
+
+        Some(unsafe { cfg_obj_asboolean(self.cfg_obj) })
+    }
+
+    /// A duration in seconds, with `unlimited` as 0.
+    pub fn as_duration(&self) -> Option<u32> {
+        if !unsafe { cfg_obj_isduration(self.cfg_obj) } {
+            return None;
+        }
+
+        Some(unsafe { cfg_obj_asduration(self.cfg_obj) })
+    }
+
+    /// An address prefix such as `192.0.2.0/24` in an address match list.
+    pub fn as_netprefix(&self) -> Option<(IpAddr, u32)> {
+        if !unsafe { cfg_obj_isnetprefix(self.cfg_obj) } {
+            return None;
+        }
+
+        let mut netaddr: isc_netaddr_t = unsafe { std::mem::zeroed() };
+        let mut prefixlen = 0;
+        unsafe { cfg_obj_asnetprefix(self.cfg_obj, &mut netaddr, &mut prefixlen) };
+
+        Some((ip_addr(&netaddr)?, prefixlen))
+    }
+
+    /// An address and port such as the `inet` address of `controls`, where
+    /// `*` is the unspecified address.
+    pub fn as_sockaddr(&self) -> Option<SocketAddr> {
+        if !unsafe { cfg_obj_issockaddr(self.cfg_obj) } {
+            return None;
+        }
+
+        let sockaddr = unsafe { cfg_obj_assockaddr(self.cfg_obj) };
+        let mut netaddr: isc_netaddr_t = unsafe { std::mem::zeroed() };
+        let port = unsafe {
+            isc_netaddr_fromsockaddr(&mut netaddr, sockaddr);
+            isc_sockaddr_getport(sockaddr)
+        };
+
+        Some(SocketAddr::new(ip_addr(&netaddr)?, port))
+    }
+
+    pub fn is_map(&self) -> bool {
+        unsafe { cfg_obj_ismap(self.cfg_obj) }
+    }
+
+    pub fn is_list(&self) -> bool {
+        unsafe { cfg_obj_islist(self.cfg_obj) }
+    }
+
+    pub fn is_tuple(&self) -> bool {
+        unsafe { cfg_obj_istuple(self.cfg_obj) }
+    }
+
+    pub fn is_void(&self) -> bool {
+        unsafe { cfg_obj_isvoid(self.cfg_obj) }
+    }
+
+    /// Whether this is a `key "name"` element of an address match list,
+    /// whose name [`as_str`](Self::as_str) returns.
+    pub fn is_keyref(&self) -> bool {
+        unsafe { cfg_obj_istype(self.cfg_obj, addr_of!(cfg_type_keyref)) }
+    }
+
+    /// The 1-based line the object was parsed from, or 0 if unknown.
+    pub fn line(&self) -> u32 {
+        unsafe { cfg_obj_line(self.cfg_obj) }
+    }
+
+    /// The file the object was parsed from, which differs from the root
+    /// file for objects coming from an `include`. For a copy of an included
+    /// file, this is the original.
+    pub fn file(&self) -> Option<&'a str> {
+        let file = unsafe { cfg_obj_file(self.cfg_obj) };
+        if file.is_null() {
+            return None;
+        }
+
+        let file = unsafe { CStr::from_ptr(file) }.to_str().ok()?;
+        Some(self.originals.get(file).map_or(file, String::as_str))
+    }
+
+    pub fn as_ptr(&self) -> *const cfg_obj_t {
+        self.cfg_obj
+    }
+}
+
+/// Formats an address the way BIND does and parses it back, dropping the
+/// zone index of scoped IPv6 addresses.
+fn ip_addr(netaddr: &isc_netaddr_t) -> Option<IpAddr> {
+    let mut buf = [0 as c_char; 128];
+    unsafe { isc_netaddr_format(netaddr, buf.as_mut_ptr(), buf.len() as u32) };
+
+    let text = unsafe { CStr::from_ptr(buf.as_ptr()) }.to_str().ok()?;
+    text.split('%').next()?.parse().ok()
+}
@@ -1,7 +1,6 @@
 use std::{
     error::Error,
-    ffi::CString,
-    os::raw::c_char,
+    ffi::{CStr, CString},
     path::Path,
     ptr::{addr_of, null_mut},
 };
@@ -11,6 +10,8 @@ use crate::{
     cfg_parser_t, cfg_type_namedconf, isc_result_ISC_R_SUCCESS,
 };
 
+use crate::path::{IncludedFiles, PathResolver};
+
 use super::{buffer::IscBuffer, log::IscLog, mem::IscMem, obj::Obj};
 
 pub struct IscParser {
@@ -49,8 +50,40 @@ impl IscParser {
         Ok(Obj::new(self, obj))
     }
 
+    /// Parses a configuration file with the paths of its `include`
+    /// statements, and of those in the files it includes, resolved through
+    /// `resolver`, using the `directory` set in the file's own `options`
+    /// when there is one.
+    pub fn parse_file_with(
+        &self,
+        path: &Path,
+        resolver: &PathResolver,
+    ) -> Result<Obj, Box<dyn Error>> {
+        self.parse_included(path, &IncludedFiles::read(path, resolver)?)
+    }
+
+    /// Parses the configuration at `path` with its includes resolved as in
+    /// `included`, which must outlive the parse for libisccfg to open the
+    /// copies it made.
+    pub fn parse_included(
+        &self,
+        path: &Path,
+        included: &IncludedFiles,
+    ) -> Result<Obj, Box<dyn Error>> {
+        let file = CString::new(path.to_str().ok_or("invalid path")?)?;
+        let obj = self.parse_buffer(included.text(), &file)?;
+
+        Ok(obj.with_originals(included.originals().clone()))
+    }
+
     #[allow(dead_code)]
     pub fn parse_string(&self, str: &str) -> Result<Obj, Box<dyn Error>> {
+        let file = unsafe { CStr::from_bytes_with_nul_unchecked(b"named.conf\0") };
+        self.parse_buffer(str, file)
+    }
+
+    /// Parses `str` as if read from `file`.
+    fn parse_buffer(&self, str: &str, file: &CStr) -> Result<Obj, Box<dyn Error>> {
         let mut conf = null_mut();
 
         let isc_buffer = IscBuffer::from_str(str);
@@ -61,7 +94,7 @@ impl IscParser {
             cfg_parse_buffer(
                 self.isc_parser,
                 isc_buffer.as_ptr(),
-                "named.conf\0".as_ptr() as *const c_char,
+                file.as_ptr(),
                 0,
                 addr_of!(cfg_type_namedconf),
                 0,
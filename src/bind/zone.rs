@@ -1,37 +1,229 @@
-use std::{ffi::CStr, ptr::null_mut};
+use std::{
+    error::Error,
+    ffi::{CStr, CString},
+    path::Path,
+    ptr::null_mut,
+};
 
 use crate::{
-    dns_fixedname_initname, dns_master_style_default, dns_masterformat_t_dns_masterformat_text,
-    dns_name_fromtext, dns_rootname, dns_zone_create, dns_zone_detach, dns_zone_load,
-    dns_zone_setclass, dns_zone_setdbtype, dns_zone_setmaxttl, dns_zone_setoption,
-    dns_zone_setorigin, dns_zone_setstream, dns_zone_settype, dns_zone_t,
-    dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRR, dns_zoneopt_t_DNS_ZONEOPT_CHECKINTEGRITY,
-    dns_zoneopt_t_DNS_ZONEOPT_CHECKMX, dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMES,
-    dns_zoneopt_t_DNS_ZONEOPT_CHECKNS, dns_zoneopt_t_DNS_ZONEOPT_CHECKSIBLING,
-    dns_zoneopt_t_DNS_ZONEOPT_CHECKSPF, dns_zoneopt_t_DNS_ZONEOPT_CHECKSVCB,
-    dns_zoneopt_t_DNS_ZONEOPT_CHECKWILDCARD, dns_zoneopt_t_DNS_ZONEOPT_MANYERRORS,
-    dns_zoneopt_t_DNS_ZONEOPT_NOMERGE, dns_zoneopt_t_DNS_ZONEOPT_WARNMXCNAME,
-    dns_zoneopt_t_DNS_ZONEOPT_WARNSRVCNAME, dns_zonetype_t_dns_zone_primary,
-    isc_result_ISC_R_SUCCESS,
+    dns_fixedname_initname, dns_master_style_default, dns_master_style_explicitttl,
+    dns_master_style_full, dns_master_style_t, dns_masterformat_t,
+    dns_masterformat_t_dns_masterformat_raw, dns_masterformat_t_dns_masterformat_text,
+    dns_name_fromtext, dns_rootname, dns_zone_create, dns_zone_detach, dns_zone_getserial,
+    dns_zone_load, dns_zone_setclass, dns_zone_setdbtype, dns_zone_setfile, dns_zone_setmaxttl,
+    dns_zone_setoption, dns_zone_setorigin, dns_zone_setstream, dns_zone_settype, dns_zone_t,
+    dns_zoneopt_t, dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRR, dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRRFAIL,
+    dns_zoneopt_t_DNS_ZONEOPT_CHECKINTEGRITY, dns_zoneopt_t_DNS_ZONEOPT_CHECKMX,
+    dns_zoneopt_t_DNS_ZONEOPT_CHECKMXFAIL, dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMES,
+    dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMESFAIL, dns_zoneopt_t_DNS_ZONEOPT_CHECKNS,
+    dns_zoneopt_t_DNS_ZONEOPT_CHECKSIBLING, dns_zoneopt_t_DNS_ZONEOPT_CHECKSPF,
+    dns_zoneopt_t_DNS_ZONEOPT_CHECKSVCB, dns_zoneopt_t_DNS_ZONEOPT_CHECKWILDCARD,
+    dns_zoneopt_t_DNS_ZONEOPT_IGNOREMXCNAME, dns_zoneopt_t_DNS_ZONEOPT_IGNORESRVCNAME,
+    dns_zoneopt_t_DNS_ZONEOPT_MANYERRORS, dns_zoneopt_t_DNS_ZONEOPT_NOMERGE,
+    dns_zoneopt_t_DNS_ZONEOPT_WARNMXCNAME, dns_zoneopt_t_DNS_ZONEOPT_WARNSRVCNAME,
+    dns_zonetype_t_dns_zone_primary, isc_result_ISC_R_SUCCESS,
 };
 
+use serde::Deserialize;
+
 use crate::bind::mem::IscMem;
+use crate::dns::{
+    diff::Ixfr,
+    record::{RData, Record},
+    serial::{self, SerialScheme},
+};
 
 use super::{
     buffer::{IscBuffer, MemFile},
-    log::IscLog,
+    db::DnsDb,
+    log::{IscLog, SavedContext},
 };
 
+/// Master file formats BIND 9.20 can load and dump. The `map` format was
+/// removed in 9.20.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasterFormat {
+    Text,
+    Raw,
+}
+
+impl MasterFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(MasterFormat::Text),
+            "raw" => Some(MasterFormat::Raw),
+            _ => None,
+        }
+    }
+
+    pub fn as_raw(&self) -> dns_masterformat_t {
+        match self {
+            MasterFormat::Text => dns_masterformat_t_dns_masterformat_text,
+            MasterFormat::Raw => dns_masterformat_t_dns_masterformat_raw,
+        }
+    }
+}
+
+/// Master file styles for dumping, after `named-compilezone -s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DumpStyle {
+    /// Relative names, with owner, TTL and class omitted where repeated.
+    Relative,
+    /// Like `Relative`, but with the TTL on every record.
+    ExplicitTtl,
+    /// Canonical form: absolute names with TTL and class on every record.
+    Full,
+}
+
+impl DumpStyle {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "relative" => Some(DumpStyle::Relative),
+            "explicitttl" => Some(DumpStyle::ExplicitTtl),
+            "full" => Some(DumpStyle::Full),
+            _ => None,
+        }
+    }
+
+    pub fn as_ptr(&self) -> *const dns_master_style_t {
+        unsafe {
+            match self {
+                DumpStyle::Relative => &dns_master_style_default,
+                DumpStyle::ExplicitTtl => &dns_master_style_explicitttl,
+                DumpStyle::Full => &dns_master_style_full,
+            }
+        }
+    }
+}
+
+/// How a check reports what it finds, as in `check-names warn;`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckMode {
+    Fail,
+    Warn,
+    Ignore,
+}
+
+impl CheckMode {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "fail" => Some(CheckMode::Fail),
+            "warn" => Some(CheckMode::Warn),
+            "ignore" => Some(CheckMode::Ignore),
+            _ => None,
+        }
+    }
+
+    fn flags(&self, check: dns_zoneopt_t, fail: dns_zoneopt_t) -> dns_zoneopt_t {
+        match self {
+            CheckMode::Fail => check | fail,
+            CheckMode::Warn => check,
+            CheckMode::Ignore => 0,
+        }
+    }
+}
+
+/// The zone options that affect loading. The defaults are the checks
+/// `named-checkzone` enables, with problems reported as warnings.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ZoneOptions {
+    pub check_names: CheckMode,
+    pub check_mx: CheckMode,
+    pub check_dup_records: CheckMode,
+    pub check_mx_cname: CheckMode,
+    pub check_srv_cname: CheckMode,
+    pub check_spf: bool,
+    pub check_integrity: bool,
+    pub check_wildcard: bool,
+    pub check_sibling: bool,
+    pub check_svcb: bool,
+    /// 0 for unlimited.
+    pub max_zone_ttl: u32,
+}
+
+impl Default for ZoneOptions {
+    fn default() -> Self {
+        ZoneOptions {
+            check_names: CheckMode::Warn,
+            check_mx: CheckMode::Warn,
+            check_dup_records: CheckMode::Warn,
+            check_mx_cname: CheckMode::Warn,
+            check_srv_cname: CheckMode::Warn,
+            check_spf: true,
+            check_integrity: true,
+            check_wildcard: true,
+            check_sibling: true,
+            check_svcb: true,
+            max_zone_ttl: 0,
+        }
+    }
+}
+
+impl ZoneOptions {
+    fn flags(&self) -> dns_zoneopt_t {
+        let mut flags = dns_zoneopt_t_DNS_ZONEOPT_MANYERRORS
+            | self.check_names.flags(
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMES,
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMESFAIL,
+            )
+            | self.check_mx.flags(
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKMX,
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKMXFAIL,
+            )
+            | self.check_dup_records.flags(
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRR,
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRRFAIL,
+            );
+
+        // MX and SRV records pointing at CNAMEs are errors unless these
+        // ask for a warning or nothing at all
+        flags |= match self.check_mx_cname {
+            CheckMode::Fail => 0,
+            CheckMode::Warn => dns_zoneopt_t_DNS_ZONEOPT_WARNMXCNAME,
+            CheckMode::Ignore => dns_zoneopt_t_DNS_ZONEOPT_IGNOREMXCNAME,
+        };
+        flags |= match self.check_srv_cname {
+            CheckMode::Fail => 0,
+            CheckMode::Warn => dns_zoneopt_t_DNS_ZONEOPT_WARNSRVCNAME,
+            CheckMode::Ignore => dns_zoneopt_t_DNS_ZONEOPT_IGNORESRVCNAME,
+        };
+
+        let checks = [
+            (self.check_spf, dns_zoneopt_t_DNS_ZONEOPT_CHECKSPF),
+            (
+                self.check_integrity,
+                dns_zoneopt_t_DNS_ZONEOPT_CHECKINTEGRITY | dns_zoneopt_t_DNS_ZONEOPT_CHECKNS,
+            ),
+            (self.check_wildcard, dns_zoneopt_t_DNS_ZONEOPT_CHECKWILDCARD),
+            (self.check_sibling, dns_zoneopt_t_DNS_ZONEOPT_CHECKSIBLING),
+            (self.check_svcb, dns_zoneopt_t_DNS_ZONEOPT_CHECKSVCB),
+        ];
+        for (enabled, flag) in checks {
+            if enabled {
+                flags |= flag;
+            }
+        }
+
+        flags
+    }
+}
+
 pub struct DnsZone {
     log: IscLog,
-    _mem: IscMem,
+    mem: IscMem,
     zone: *mut dns_zone_t,
 }
 
 impl DnsZone {
     pub fn new() -> Self {
         let mem = IscMem::new();
-        let log = IscLog::new(&mem);
+        // the zone's log only takes over the global context while loading
+        let log = {
+            let _saved = SavedContext::save();
+            IscLog::new(&mem)
+        };
 
         let mut zone: *mut dns_zone_t = std::ptr::null_mut();
 
@@ -39,14 +231,46 @@ impl DnsZone {
             dns_zone_create(&mut zone, mem.as_ptr(), 0);
         }
 
-        DnsZone {
-            _mem: mem,
-            zone,
-            log,
+        DnsZone { mem, zone, log }
+    }
+
+    pub fn check(&self, zonename: &str, text: &str) -> Result<String, Box<dyn Error>> {
+        self.check_with(zonename, text, &ZoneOptions::default())
+    }
+
+    /// Loads master file text with the checks of `options`, returning
+    /// what BIND logged. Fails when `zonename` is not a valid origin.
+    pub fn check_with(
+        &self,
+        zonename: &str,
+        text: &str,
+        options: &ZoneOptions,
+    ) -> Result<String, Box<dyn Error>> {
+        unsafe {
+            self.configure(zonename, options)?;
+
+            let input = MemFile::from_str(text);
+            let ret = dns_zone_setstream(
+                self.zone,
+                input.as_ptr(),
+                dns_masterformat_t_dns_masterformat_text,
+                &dns_master_style_default,
+            );
+            if ret != isc_result_ISC_R_SUCCESS {
+                return Err("dns_zone_setstream failed".into());
+            }
+            // a zone that fails to load has no database, which db() reports
+            let _saved = SavedContext::save();
+            self.log.set_context();
+            dns_zone_load(self.zone, false);
+
+            Ok(self.log.to_string())
         }
     }
 
-    pub fn check(&self, zonename: &str, text: &str) -> String {
+    /// Sets up the zone as a primary for `zonename` with the checks of
+    /// `options`.
+    fn configure(&self, zonename: &str, options: &ZoneOptions) -> Result<(), Box<dyn Error>> {
         unsafe {
             dns_zone_settype(self.zone, dns_zonetype_t_dns_zone_primary);
 
@@ -55,51 +279,129 @@ impl DnsZone {
             let origin = dns_fixedname_initname(&mut fixorigin);
             let ret = dns_name_fromtext(origin, buffer.as_ptr(), dns_rootname, 0, null_mut());
             if ret != isc_result_ISC_R_SUCCESS {
-                panic!("dns_name_fromtext failed");
+                return Err(format!("{}: invalid zone name", zonename).into());
             }
             let ret = dns_zone_setorigin(self.zone, origin);
             if ret != isc_result_ISC_R_SUCCESS {
-                panic!("dns_zone_setorigin failed");
+                return Err("dns_zone_setorigin failed".into());
             }
 
             let dbtype = [CStr::from_bytes_with_nul_unchecked(b"qpzone\0").as_ptr()];
             dns_zone_setdbtype(self.zone, 1, dbtype.as_ptr());
 
             dns_zone_setclass(self.zone, 1);
-            let zone_options = dns_zoneopt_t_DNS_ZONEOPT_CHECKNS
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKMX
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKDUPRR
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKSPF
-                | dns_zoneopt_t_DNS_ZONEOPT_MANYERRORS
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKNAMES
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKINTEGRITY
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKSIBLING
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKSVCB
-                | dns_zoneopt_t_DNS_ZONEOPT_CHECKWILDCARD
-                | dns_zoneopt_t_DNS_ZONEOPT_WARNMXCNAME
-                | dns_zoneopt_t_DNS_ZONEOPT_WARNSRVCNAME;
-            dns_zone_setoption(self.zone, zone_options, true);
+            dns_zone_setoption(self.zone, options.flags(), true);
             dns_zone_setoption(self.zone, dns_zoneopt_t_DNS_ZONEOPT_NOMERGE, true);
-            dns_zone_setmaxttl(self.zone, 0);
+            dns_zone_setmaxttl(self.zone, options.max_zone_ttl);
+        }
 
-            let input = MemFile::from_str(text);
-            let ret = dns_zone_setstream(
-                self.zone,
-                input.as_ptr(),
-                dns_masterformat_t_dns_masterformat_text,
+        Ok(())
+    }
+
+    /// Everything BIND logged while loading the zone.
+    pub fn log(&self) -> String {
+        self.log.to_string()
+    }
+
+    /// Loads a primary zone from master file text, failing with BIND's log
+    /// when it does not load.
+    pub fn load(zonename: &str, text: &str) -> Result<Self, Box<dyn Error>> {
+        let zone = DnsZone::new();
+        let log = zone.check(zonename, text)?;
+
+        match zone.db() {
+            Ok(_) => Ok(zone),
+            Err(_) => Err(format!("{}: failed to load zone\n{}", zonename, log).into()),
+        }
+    }
+
+    /// Loads a primary zone from a master file in `format`, failing with
+    /// BIND's log when it does not load.
+    pub fn load_file(
+        zonename: &str,
+        path: &Path,
+        format: MasterFormat,
+        options: &ZoneOptions,
+    ) -> Result<Self, Box<dyn Error>> {
+        let zone = DnsZone::new();
+        let file = CString::new(path.to_str().ok_or("invalid path")?)?;
+
+        unsafe {
+            zone.configure(zonename, options)?;
+
+            let ret = dns_zone_setfile(
+                zone.zone,
+                file.as_ptr(),
+                format.as_raw(),
                 &dns_master_style_default,
             );
             if ret != isc_result_ISC_R_SUCCESS {
-                panic!("dns_zone_setstream failed");
-            }
-            let ret = dns_zone_load(self.zone, false);
-            if ret != isc_result_ISC_R_SUCCESS {
-                println!("dns_zone_load failed");
+                return Err("dns_zone_setfile failed".into());
             }
+            let _saved = SavedContext::save();
+            zone.log.set_context();
+            dns_zone_load(zone.zone, false);
+        }
 
-            self.log.to_string()
+        match zone.db() {
+            Ok(_) => Ok(zone),
+            Err(_) => Err(format!("{}: failed to load zone\n{}", zonename, zone.log).into()),
         }
     }
+
+    /// Writes the loaded zone in `format` to `output`, or to standard output.
+    /// Names are written in DNSSEC canonical order, as the database keeps
+    /// them.
+    pub fn dump(
+        &self,
+        output: Option<&Path>,
+        format: MasterFormat,
+        style: DumpStyle,
+    ) -> Result<(), Box<dyn Error>> {
+        self.db()?.dump(&self.mem, output, format, style)
+    }
+
+    /// The SOA serial of the zone, once it has been loaded.
+    pub fn serial(&self) -> Option<u32> {
+        let mut serial = 0;
+        let ret = unsafe { dns_zone_getserial(self.zone, &mut serial) };
+
+        (ret == isc_result_ISC_R_SUCCESS).then_some(serial)
+    }
+
+    /// The serial the next version of the loaded zone should use, following
+    /// the scheme of the current one.
+    pub fn next_serial(&self) -> Option<(SerialScheme, u32)> {
+        let serial = self.serial()?;
+        let now = serial::now();
+        let scheme = SerialScheme::detect(serial, now);
+
+        Some((scheme, scheme.next(serial, now)))
+    }
+
+    /// The database of the loaded zone.
+    pub fn db(&self) -> Result<DnsDb, Box<dyn Error>> {
+        DnsDb::from_zone(self.zone)
+    }
+
+    /// Every record of the loaded zone, with typed rdata.
+    pub fn records(&self) -> Result<Vec<Record>, Box<dyn Error>> {
+        self.db()?.records()
+    }
+
+    /// The IXFR-style difference from this zone to `newer`, or `None` when
+    /// their contents are the same.
+    pub fn ixfr(&self, zonename: &str, newer: &DnsZone) -> Result<Option<Ixfr>, Box<dyn Error>> {
+        let db = self.db()?;
+        let old_soa = db
+            .records()?
+            .into_iter()
+            .find(|r| matches!(r.data, RData::SOA { .. }))
+            .ok_or("zone has no SOA record")?;
+        let tuples = db.diff(&self.mem, &newer.db()?)?;
+
+        Ok(Ixfr::new(zonename, old_soa, tuples, serial::now()))
+    }
 }
 
 impl Drop for DnsZone {
@@ -134,7 +436,124 @@ ns2 IN  A 192.168.1.2
 "#;
 
         let zone = DnsZone::new();
-        let result = zone.check(zonename, &text);
+        let result = zone.check(zonename, &text).unwrap();
         println!("{}", result);
+
+        assert_eq!(zone.serial(), Some(2023101001));
+        let (scheme, next) = zone.next_serial().unwrap();
+        assert_eq!(scheme, SerialScheme::Date);
+        assert!(next > 2023101001);
+
+        let records = zone.records().unwrap();
+        assert_eq!(records.len(), 5);
+        let ns1 = records
+            .iter()
+            .find(|r| r.name == "ns1.example.com.")
+            .unwrap();
+        assert_eq!(ns1.ttl, 86400);
+        assert_eq!(ns1.class, "IN");
+        assert_eq!(ns1.data, RData::A("192.168.1.1".parse().unwrap()));
+        assert!(records.iter().any(|r| matches!(
+            r.data,
+            RData::SOA {
+                serial: 2023101001,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_dns_zone_ixfr() {
+        let zone = |serial: u32, www: &str| {
+            format!(
+                "$TTL 300\n\
+                 @ IN SOA ns1.example.com. admin.example.com. {} 3600 900 604800 300\n\
+                 @ IN NS ns1.example.com.\n\
+                 ns1 IN A 192.0.2.1\n\
+                 www IN A {}\n",
+                serial, www
+            )
+        };
+
+        let old = DnsZone::load("example.com", &zone(1, "192.0.2.2")).unwrap();
+        let new = DnsZone::load("example.com", &zone(1, "192.0.2.3")).unwrap();
+        let ixfr = old.ixfr("example.com", &new).unwrap().unwrap();
+        assert!(ixfr.serial_bumped);
+        assert_eq!(
+            ixfr.to_nsupdate(),
+            "zone example.com.\n\
+             update delete www.example.com. IN A 192.0.2.2\n\
+             update add example.com. 300 IN SOA ns1.example.com. admin.example.com. 2 3600 900 604800 300\n\
+             update add www.example.com. 300 IN A 192.0.2.3\n\
+             send\n"
+        );
+
+        let same = DnsZone::load("example.com", &zone(1, "192.0.2.2")).unwrap();
+        assert!(old.ixfr("example.com", &same).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_dns_zone_invalid_origin() {
+        let text = "$TTL 300\n\
+                    @ IN SOA ns1.example.com. admin.example.com. 1 3600 900 604800 300\n\
+                    @ IN NS ns1.example.com.\n";
+
+        let err = DnsZone::load("a..example.com", text).err().unwrap();
+        assert_eq!(err.to_string(), "a..example.com: invalid zone name");
+
+        let options = ZoneOptions::default();
+        let path = Path::new("db.example.com");
+        let file = DnsZone::load_file("a..example.com", path, MasterFormat::Text, &options);
+        assert!(file.is_err());
+    }
+
+    #[test]
+    fn test_dns_zone_raw_roundtrip() {
+        let text = "$TTL 300\n\
+                    @ IN SOA ns1.example.com. admin.example.com. 1 3600 900 604800 300\n\
+                    @ IN NS ns1.example.com.\n\
+                    ns1 IN A 192.0.2.1\n";
+        let path = std::env::temp_dir().join(format!("bls-test-{}.raw", std::process::id()));
+
+        let zone = DnsZone::load("example.com", text).unwrap();
+        zone.dump(Some(&path), MasterFormat::Raw, DumpStyle::Relative)
+            .unwrap();
+        let raw = DnsZone::load_file(
+            "example.com",
+            &path,
+            MasterFormat::Raw,
+            &ZoneOptions::default(),
+        )
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(raw.records().unwrap(), zone.records().unwrap());
+    }
+
+    #[test]
+    fn test_dns_zone_dump_full() {
+        let text = "$TTL 300\n\
+                    @ IN SOA ns1 admin 1 3600 900 604800 300\n\
+                    www IN A 192.0.2.2\n\
+                    @ NS ns1\n\
+                    ns1 IN A 192.0.2.1\n";
+        let path = std::env::temp_dir().join(format!("bls-test-{}.db", std::process::id()));
+
+        let zone = DnsZone::load("example.com", text).unwrap();
+        zone.dump(Some(&path), MasterFormat::Text, DumpStyle::Full)
+            .unwrap();
+        let dump = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let records = dump
+            .lines()
+            .filter(|line| !line.starts_with(';') && !line.trim().is_empty())
+            .map(|line| line.split_whitespace().take(4).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert!(records.contains(&vec!["www.example.com.", "300", "IN", "A"]));
+        assert!(records.contains(&vec!["example.com.", "300", "IN", "NS"]));
+        let www = records.iter().position(|r| r[0] == "www.example.com.");
+        let ns1 = records.iter().position(|r| r[0] == "ns1.example.com.");
+        assert!(ns1 < www);
     }
 }
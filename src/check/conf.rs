@@ -0,0 +1,108 @@
+//! Checks of a configuration file.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use crate::bind::{log::IscLog, mem::IscMem, obj::Obj, parser::IscParser};
+use crate::config::ProjectConfig;
+use crate::diagnostic::{from_bind_log, Diagnostic, Severity, LINE_END};
+use crate::lsp::code_action::conf_fixes;
+use crate::path::{IncludedFiles, PathResolver};
+use crate::syntax::{conf, Pos};
+
+use super::lint::lint_conf;
+use super::paths::missing_paths;
+
+/// A configuration as checked by [`check_conf`].
+pub struct ConfCheck<'a> {
+    /// `None` when the configuration does not parse.
+    pub obj: Option<Obj<'a>>,
+    /// Whether the configuration passed BIND's checks. Zones of one that
+    /// did not may have names BIND cannot load.
+    pub valid: bool,
+    /// The resolver for the paths of the configuration, with its
+    /// `directory` applied once it parses.
+    pub resolver: PathResolver,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Parses and checks the configuration at `path` like `named-checkconf`,
+/// with the checks and path mappings of the project configuration, then
+/// runs the lint rules and warns about directories it refers to that do
+/// not exist.
+pub fn check_conf<'a>(
+    parser: &'a IscParser,
+    log: &IscLog,
+    mem: &IscMem,
+    config: &ProjectConfig,
+    path: &Path,
+) -> ConfCheck<'a> {
+    log.clear();
+    let resolver = config.resolver(path);
+    let included = match IncludedFiles::read(path, &resolver) {
+        Ok(included) => included,
+        Err(err) => {
+            let message = format!("{}: {}", path.display(), err);
+            return ConfCheck {
+                obj: None,
+                valid: false,
+                resolver,
+                diagnostics: vec![Diagnostic::line(path, 0, Severity::Error, message)],
+            };
+        }
+    };
+    let Ok(obj) = parser.parse_included(path, &included) else {
+        let mut diagnostics = from_bind_log(&included.restore(&log.to_string()), path);
+        add_fixes(&mut diagnostics);
+        return ConfCheck {
+            obj: None,
+            valid: false,
+            resolver,
+            diagnostics,
+        };
+    };
+
+    let valid = obj.check_with(log, mem, config.check);
+    let mut diagnostics = from_bind_log(&included.restore(&log.to_string()), path);
+    add_fixes(&mut diagnostics);
+    diagnostics.extend(lint_conf(obj.root(), path));
+
+    let resolver = resolver.with_config(obj.root());
+    for missing in missing_paths(obj.root(), &resolver) {
+        let file = missing.conf_file.as_deref().unwrap_or(path);
+        let line = missing.line.saturating_sub(1);
+        diagnostics.push(Diagnostic::new(
+            file,
+            Pos { line, column: 0 },
+            Pos {
+                line,
+                column: LINE_END,
+            },
+            Severity::Warning,
+            "missing-directory",
+            format!(
+                "{} '{}' does not exist ({})",
+                missing.clause,
+                missing.path,
+                missing.resolved.display()
+            ),
+        ));
+    }
+
+    ConfCheck {
+        obj: Some(obj),
+        valid,
+        resolver,
+        diagnostics,
+    }
+}
+
+/// Attaches the quick fixes the language server offers for BIND's messages.
+fn add_fixes(diagnostics: &mut [Diagnostic]) {
+    let mut texts = HashMap::new();
+    for diagnostic in diagnostics.iter_mut().filter(|d| d.source == "bind") {
+        let text = texts.entry(diagnostic.file.clone()).or_insert_with(|| {
+            conf::parse(&fs::read_to_string(&diagnostic.file).unwrap_or_default())
+        });
+        diagnostic.fixes = conf_fixes(text, diagnostic.start.line, &diagnostic.message);
+    }
+}
@@ -0,0 +1,209 @@
+//! Forward and reverse zones hosted together should agree: every address
+//! record has a PTR back to its owner, and every PTR target has an address
+//! record for the address the PTR stands for.
+
+use std::{net::IpAddr, path::PathBuf};
+
+use crate::dns::{
+    fqdn,
+    record::{RData, Record},
+    reverse::{reverse_address, reverse_name},
+    same_name,
+};
+
+/// The records of a zone loaded from `file`.
+#[derive(Debug, Clone)]
+pub struct LoadedZone {
+    pub origin: String,
+    pub file: PathBuf,
+    pub records: Vec<Record>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub origin: String,
+    pub file: PathBuf,
+    pub record: Record,
+    pub message: String,
+}
+
+/// Checks address records against PTR records, only where the zone that
+/// should hold the counterpart is among `zones`.
+pub fn check_consistency(zones: &[LoadedZone]) -> Vec<Mismatch> {
+    let mut mismatches = vec![];
+
+    for zone in zones {
+        for record in &zone.records {
+            let message = match &record.data {
+                RData::A(addr) => forward_mismatch(zones, &record.name, IpAddr::V4(*addr)),
+                RData::AAAA(addr) => forward_mismatch(zones, &record.name, IpAddr::V6(*addr)),
+                RData::PTR(target) => reverse_mismatch(zones, &record.name, target),
+                _ => None,
+            };
+
+            if let Some(message) = message {
+                mismatches.push(Mismatch {
+                    origin: zone.origin.clone(),
+                    file: zone.file.clone(),
+                    record: record.clone(),
+                    message,
+                });
+            }
+        }
+    }
+
+    mismatches
+}
+
+fn forward_mismatch(zones: &[LoadedZone], owner: &str, addr: IpAddr) -> Option<String> {
+    if owner.starts_with("*.") {
+        return None;
+    }
+
+    let name = reverse_name(addr);
+    let reverse = hosting_zone(zones, &name)?;
+    let ptrs = reverse
+        .records
+        .iter()
+        .filter(|r| same_name(&r.name, &name))
+        .filter_map(|r| match &r.data {
+            RData::PTR(target) => Some(target),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if ptrs.iter().any(|target| same_name(target, owner)) {
+        return None;
+    }
+
+    Some(match ptrs.is_empty() {
+        true => format!(
+            "{} has no PTR record in {} pointing back to {}",
+            addr, reverse.origin, owner
+        ),
+        false => format!(
+            "the PTR record for {} in {} points to {}, not {}",
+            addr,
+            reverse.origin,
+            ptrs.iter()
+                .map(|t| t.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            owner
+        ),
+    })
+}
+
+fn reverse_mismatch(zones: &[LoadedZone], owner: &str, target: &str) -> Option<String> {
+    let addr = reverse_address(owner)?;
+    let forward = hosting_zone(zones, target)?;
+    let addrs = forward
+        .records
+        .iter()
+        .filter(|r| same_name(&r.name, target))
+        .filter_map(|r| match r.data {
+            RData::A(addr) => Some(IpAddr::V4(addr)),
+            RData::AAAA(addr) => Some(IpAddr::V6(addr)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    if addrs.contains(&addr) {
+        return None;
+    }
+
+    Some(format!(
+        "{} has no {} record for {} in {}",
+        target,
+        match addr {
+            IpAddr::V4(_) => "A",
+            IpAddr::V6(_) => "AAAA",
+        },
+        addr,
+        forward.origin
+    ))
+}
+
+/// The most specific zone among `zones` that `name` belongs to.
+fn hosting_zone<'a>(zones: &'a [LoadedZone], name: &str) -> Option<&'a LoadedZone> {
+    let name = fqdn(name).to_ascii_lowercase();
+
+    zones
+        .iter()
+        .filter(|zone| {
+            let origin = fqdn(&zone.origin).to_ascii_lowercase();
+            name == origin || name.ends_with(&format!(".{}", origin))
+        })
+        .max_by_key(|zone| zone.origin.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(origin: &str, records: &[(&str, &str, &str)]) -> LoadedZone {
+        LoadedZone {
+            origin: origin.to_string(),
+            file: PathBuf::from(format!("db.{}", origin)),
+            records: records
+                .iter()
+                .map(|(name, rtype, data)| Record {
+                    name: name.to_string(),
+                    ttl: 300,
+                    class: "IN".to_string(),
+                    data: RData::parse(rtype, data),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let zones = [
+            zone(
+                "example.com.",
+                &[
+                    ("www.example.com.", "A", "192.168.1.10"),
+                    ("mail.example.com.", "A", "192.168.1.20"),
+                    ("ftp.example.com.", "A", "192.168.1.30"),
+                    ("ext.example.com.", "A", "198.51.100.1"),
+                ],
+            ),
+            zone(
+                "1.168.192.in-addr.arpa.",
+                &[
+                    ("10.1.168.192.in-addr.arpa.", "PTR", "WWW.example.com."),
+                    ("20.1.168.192.in-addr.arpa.", "PTR", "smtp.example.com."),
+                    ("40.1.168.192.in-addr.arpa.", "PTR", "old.example.com."),
+                    ("50.1.168.192.in-addr.arpa.", "PTR", "host.example.net."),
+                ],
+            ),
+        ];
+
+        let mismatches = check_consistency(&zones)
+            .into_iter()
+            .map(|m| (m.record.name, m.message))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mismatches,
+            vec![
+                (
+                    "mail.example.com.".to_string(),
+                    "the PTR record for 192.168.1.20 in 1.168.192.in-addr.arpa. points to smtp.example.com., not mail.example.com.".to_string()
+                ),
+                (
+                    "ftp.example.com.".to_string(),
+                    "192.168.1.30 has no PTR record in 1.168.192.in-addr.arpa. pointing back to ftp.example.com.".to_string()
+                ),
+                (
+                    "20.1.168.192.in-addr.arpa.".to_string(),
+                    "smtp.example.com. has no A record for 192.168.1.20 in example.com.".to_string()
+                ),
+                (
+                    "40.1.168.192.in-addr.arpa.".to_string(),
+                    "old.example.com. has no A record for 192.168.1.40 in example.com.".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,592 @@
+//! Lint rules for configurations that `named-checkconf` accepts but that
+//! are operationally dubious or weaken security. Each rule has an ID that
+//! `.bls.toml` can set the severity of, or turn off.
+
+use std::path::Path;
+
+use crate::acl::AclEvaluator;
+use crate::bind::obj::ObjRef;
+use crate::diagnostic::{Diagnostic, Severity, LINE_END};
+use crate::syntax::Pos;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Practice,
+    /// Rules for a security review, as run by `bls check --security`.
+    Security,
+}
+
+pub struct Rule {
+    pub id: &'static str,
+    pub category: Category,
+    pub severity: Severity,
+    /// What the rule reports, in a sentence.
+    pub description: &'static str,
+    check: for<'a> fn(&Config<'a>, &mut Vec<Finding<'a>>),
+}
+
+/// A violation of a rule, reported on the line of `obj`.
+struct Finding<'a> {
+    obj: ObjRef<'a>,
+    message: String,
+}
+
+impl<'a> Finding<'a> {
+    fn new(obj: ObjRef<'a>, message: String) -> Self {
+        Finding { obj, message }
+    }
+}
+
+pub const RULES: &[Rule] = &[
+    Rule {
+        id: "open-recursion",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "Recursion is allowed from any address.",
+        check: open_recursion,
+    },
+    Rule {
+        id: "allow-transfer-any",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "Zone transfers are allowed to any address.",
+        check: allow_transfer_any,
+    },
+    Rule {
+        id: "open-update",
+        category: Category::Security,
+        severity: Severity::Error,
+        description: "Dynamic updates are allowed from any address.",
+        check: open_update,
+    },
+    Rule {
+        id: "version-not-hidden",
+        category: Category::Security,
+        severity: Severity::Info,
+        description: "The server answers version.bind queries with its BIND version.",
+        check: version_not_hidden,
+    },
+    Rule {
+        id: "query-logging-disabled",
+        category: Category::Practice,
+        severity: Severity::Info,
+        description: "Queries are not logged.",
+        check: query_logging_disabled,
+    },
+    Rule {
+        id: "update-by-address",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "Dynamic updates are authorized by address rather than by TSIG key.",
+        check: update_by_address,
+    },
+    Rule {
+        id: "hmac-md5-key",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "A TSIG key uses HMAC-MD5.",
+        check: hmac_md5_key,
+    },
+    Rule {
+        id: "dnssec-validation-disabled",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "DNSSEC validation is turned off.",
+        check: dnssec_validation_disabled,
+    },
+    Rule {
+        id: "missing-rate-limit",
+        category: Category::Security,
+        severity: Severity::Info,
+        description: "An authoritative server has no response rate limiting.",
+        check: missing_rate_limit,
+    },
+    Rule {
+        id: "control-without-keys",
+        category: Category::Security,
+        severity: Severity::Warning,
+        description: "A control channel listens beyond loopback without keys.",
+        check: control_without_keys,
+    },
+];
+
+pub fn rule(id: &str) -> Option<&'static Rule> {
+    RULES.iter().find(|rule| rule.id == id)
+}
+
+/// Runs every rule on a parsed configuration. Findings in included files are
+/// reported in those files, and others in `path`.
+pub fn lint_conf(root: ObjRef, path: &Path) -> Vec<Diagnostic> {
+    let config = Config::new(root);
+
+    let mut diagnostics = vec![];
+    for rule in RULES {
+        let mut findings = vec![];
+        (rule.check)(&config, &mut findings);
+
+        for finding in findings {
+            let file = finding.obj.file().map(Path::new).unwrap_or(path);
+            let line = finding.obj.line().saturating_sub(1);
+            diagnostics.push(Diagnostic::new(
+                file,
+                Pos { line, column: 0 },
+                Pos {
+                    line,
+                    column: LINE_END,
+                },
+                rule.severity,
+                rule.id,
+                finding.message,
+            ));
+        }
+    }
+
+    diagnostics
+}
+
+/// The parts of a configuration the rules look at.
+struct Config<'a> {
+    root: ObjRef<'a>,
+    options: Option<ObjRef<'a>>,
+    views: Vec<View<'a>>,
+    acls: AclEvaluator<'a>,
+}
+
+struct View<'a> {
+    name: &'a str,
+    /// The view's own clauses.
+    options: Option<ObjRef<'a>>,
+}
+
+/// A zone and the name of the view it is in.
+struct Zone<'a> {
+    name: &'a str,
+    view: Option<&'a str>,
+    options: ObjRef<'a>,
+}
+
+impl<'a> Config<'a> {
+    fn new(root: ObjRef<'a>) -> Self {
+        let views = root.map_get("view").map(|v| v.list()).unwrap_or_default();
+        let views = views
+            .iter()
+            .filter_map(|view| {
+                Some(View {
+                    name: view.tuple_get("name")?.as_str()?,
+                    options: view.tuple_get("options"),
+                })
+            })
+            .collect();
+
+        Config {
+            root,
+            options: root.map_get("options"),
+            views,
+            acls: AclEvaluator::new(root),
+        }
+    }
+
+    /// The value of a clause in a view, or in `options` when the view does
+    /// not set it. Without views, the value in `options`.
+    fn lookup(&self, view: Option<&View<'a>>, name: &str) -> Option<ObjRef<'a>> {
+        view.and_then(|v| v.options)
+            .and_then(|options| options.map_get(name))
+            .or_else(|| self.options.and_then(|options| options.map_get(name)))
+    }
+
+    /// The views, or `None` alone for a configuration without views.
+    fn scopes(&self) -> Vec<Option<&View<'a>>> {
+        if self.views.is_empty() {
+            vec![None]
+        } else {
+            self.views.iter().map(Some).collect()
+        }
+    }
+
+    fn zones(&self) -> Vec<Zone<'a>> {
+        let mut zones = vec![];
+        let maps = [(None, Some(self.root))].into_iter().chain(
+            self.views
+                .iter()
+                .map(|view| (Some(view.name), view.options)),
+        );
+        for (view, map) in maps {
+            let list = map.and_then(|m| m.map_get("zone"));
+            for zone in list.map(|z| z.list()).unwrap_or_default() {
+                let name = zone.tuple_get("name").and_then(|n| n.as_str());
+                if let (Some(name), Some(options)) = (name, zone.tuple_get("options")) {
+                    zones.push(Zone {
+                        name,
+                        view,
+                        options,
+                    });
+                }
+            }
+        }
+
+        zones
+    }
+
+    /// The maps zone options can be set in: `options`, views and zones,
+    /// each with where it is for messages.
+    fn zone_scopes(&self) -> Vec<(ObjRef<'a>, String)> {
+        let mut maps = vec![];
+        maps.extend(self.options.map(|options| (options, String::new())));
+        for view in &self.views {
+            maps.extend(
+                view.options
+                    .map(|options| (options, in_view(Some(view.name)))),
+            );
+        }
+        for zone in self.zones() {
+            let place = format!(" for zone \"{}\"{}", zone.name, in_view(zone.view));
+            maps.push((zone.options, place));
+        }
+
+        maps
+    }
+
+    /// The `key` statements at the top level and in views.
+    fn keys(&self) -> Vec<ObjRef<'a>> {
+        let maps = [Some(self.root)]
+            .into_iter()
+            .chain(self.views.iter().map(|view| view.options));
+
+        maps.flatten()
+            .filter_map(|map| map.map_get("key"))
+            .flat_map(|keys| keys.list())
+            .collect()
+    }
+}
+
+/// ` in view "name"`, or nothing outside views.
+fn in_view(view: Option<&str>) -> String {
+    match view {
+        Some(view) => format!(" in view \"{}\"", view),
+        None => String::new(),
+    }
+}
+
+/// Recursion is on by default, and `allow-recursion` falls back to
+/// `allow-query-cache` and then `allow-query` before its default of
+/// `localnets; localhost;`. Views matching only some clients are skipped.
+fn open_recursion<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    for view in config.scopes() {
+        let recursion = config.lookup(view, "recursion").and_then(|r| r.as_bool());
+        if recursion == Some(false) {
+            continue;
+        }
+        let clients = view.and_then(|v| v.options?.map_get("match-clients"));
+        if clients.is_some_and(|clients| config.acls.allows_any(clients).is_none()) {
+            continue;
+        }
+
+        let acl = ["allow-recursion", "allow-query-cache", "allow-query"]
+            .iter()
+            .find_map(|name| config.lookup(view, name).map(|acl| (*name, acl)));
+        let Some((name, acl)) = acl else {
+            continue;
+        };
+        if let Some(element) = config.acls.allows_any(acl) {
+            findings.push(Finding::new(
+                element,
+                format!(
+                    "recursion is allowed from any address{} through {}",
+                    in_view(view.map(|v| v.name)),
+                    name
+                ),
+            ));
+        }
+    }
+}
+
+fn allow_transfer_any<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    for (map, place) in config.zone_scopes() {
+        let acl = map.map_get("allow-transfer");
+        if let Some(element) = acl.and_then(|acl| config.acls.allows_any(acl)) {
+            findings.push(Finding::new(
+                element,
+                format!("zone transfers are allowed to any address{}", place),
+            ));
+        }
+    }
+}
+
+fn open_update<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    for (map, place) in config.zone_scopes() {
+        for clause in ["allow-update", "allow-update-forwarding"] {
+            let acl = map.map_get(clause);
+            if let Some(element) = acl.and_then(|acl| config.acls.allows_any(acl)) {
+                findings.push(Finding::new(
+                    element,
+                    format!("{} allows updates from any address{}", clause, place),
+                ));
+            }
+        }
+    }
+}
+
+fn version_not_hidden<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    let options = config.options.unwrap_or(config.root);
+    if options.map_get("version").is_none() {
+        findings.push(Finding::new(
+            options,
+            "version is not set, so version.bind queries reveal the BIND version".to_string(),
+        ));
+    }
+}
+
+/// `named` logs queries when `querylog` is on, or when it is not set and
+/// a `queries` logging category is defined.
+fn query_logging_disabled<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    let options = config.options.unwrap_or(config.root);
+    match options.map_get("querylog") {
+        Some(querylog) if querylog.as_bool() == Some(false) => {
+            findings.push(Finding::new(
+                querylog,
+                "query logging is turned off".to_string(),
+            ));
+        }
+        Some(_) => {}
+        None => {
+            let categories = config
+                .root
+                .map_get("logging")
+                .and_then(|l| l.map_get("category"));
+            let logged = categories
+                .map(|c| c.list())
+                .unwrap_or_default()
+                .iter()
+                .any(|c| c.tuple_get("name").and_then(|n| n.as_str()) == Some("queries"));
+            if !logged {
+                findings.push(Finding::new(
+                    options,
+                    "queries are not logged: querylog is not set and there is no queries logging category"
+                        .to_string(),
+                ));
+            }
+        }
+    }
+}
+
+fn update_by_address<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    for (map, place) in config.zone_scopes() {
+        let Some(acl) = map.map_get("allow-update") else {
+            continue;
+        };
+        // updates from any address are reported by open-update
+        if config.acls.allows_any(acl).is_some() {
+            continue;
+        }
+        if let Some(element) = config.acls.allows_by_address(acl) {
+            findings.push(Finding::new(
+                element,
+                format!(
+                    "allow-update authorizes updates by address{}; use TSIG keys or update-policy",
+                    place
+                ),
+            ));
+        }
+    }
+}
+
+fn hmac_md5_key<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    for key in config.keys() {
+        let Some(algorithm) = key.map_get("algorithm") else {
+            continue;
+        };
+        let is_md5 = algorithm
+            .as_str()
+            .is_some_and(|a| a.to_ascii_lowercase().starts_with("hmac-md5"));
+        if is_md5 {
+            let name = key.map_name().and_then(|n| n.as_str()).unwrap_or_default();
+            findings.push(Finding::new(
+                algorithm,
+                format!(
+                    "key \"{}\" uses HMAC-MD5; use hmac-sha256 or stronger",
+                    name
+                ),
+            ));
+        }
+    }
+}
+
+fn dnssec_validation_disabled<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    let mut maps = vec![(config.options, String::new())];
+    maps.extend(
+        config
+            .views
+            .iter()
+            .map(|view| (view.options, in_view(Some(view.name)))),
+    );
+
+    for (map, place) in maps {
+        let Some(validation) = map.and_then(|m| m.map_get("dnssec-validation")) else {
+            continue;
+        };
+        if validation.as_bool() == Some(false) {
+            findings.push(Finding::new(
+                validation,
+                format!("DNSSEC validation is turned off{}", place),
+            ));
+        }
+    }
+}
+
+/// Servers with primary or secondary zones should limit responses, as they
+/// answer anyone and can be used to amplify attacks.
+fn missing_rate_limit<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    let zones = config.zones();
+    for view in config.scopes() {
+        let name = view.map(|v| v.name);
+        let authoritative = zones.iter().filter(|z| z.view == name).any(|zone| {
+            let kind = zone.options.map_get("type").and_then(|t| t.as_str());
+            matches!(
+                kind.map(|k| k.to_ascii_lowercase()).as_deref(),
+                Some("primary" | "master" | "secondary" | "slave")
+            )
+        });
+        if !authoritative || config.lookup(view, "rate-limit").is_some() {
+            continue;
+        }
+
+        let obj = view
+            .and_then(|v| v.options)
+            .or(config.options)
+            .unwrap_or(config.root);
+        findings.push(Finding::new(
+            obj,
+            format!(
+                "authoritative zones are served{} without rate-limit",
+                in_view(name)
+            ),
+        ));
+    }
+}
+
+fn control_without_keys<'a>(config: &Config<'a>, findings: &mut Vec<Finding<'a>>) {
+    let controls = config.root.map_get("controls");
+    let channels = controls
+        .map(|c| c.list())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|c| c.map_get("inet"))
+        .flat_map(|inet| inet.list())
+        .collect::<Vec<_>>();
+
+    for channel in channels {
+        let Some(address) = channel.tuple_get("address").and_then(|a| a.as_sockaddr()) else {
+            continue;
+        };
+        let keys = channel
+            .tuple_get("keys")
+            .map(|k| k.list())
+            .unwrap_or_default();
+        if address.ip().is_loopback() || !keys.is_empty() {
+            continue;
+        }
+
+        findings.push(Finding::new(
+            channel,
+            format!(
+                "the control channel on {} is not loopback and has no keys",
+                address.ip()
+            ),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+
+    #[test]
+    fn test_lint_conf() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+acl "everyone" { any; };
+options {
+    version none;
+    querylog yes;
+    allow-recursion { everyone; };
+    rate-limit { responses-per-second 10; };
+};
+zone "example.com" {
+    type primary;
+    file "db.example.com";
+    allow-transfer { !192.0.2.1; any; };
+    allow-update { key "ddns"; any; };
+};
+"#,
+            )
+            .unwrap();
+
+        let diagnostics = lint_conf(obj.root(), Path::new("named.conf"));
+        let codes = diagnostics
+            .iter()
+            .map(|d| (d.code.as_deref().unwrap(), d.start.line + 1))
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec![("open-recursion", 6), ("open-update", 13)]);
+        assert_eq!(
+            diagnostics[0].message,
+            "recursion is allowed from any address through allow-recursion"
+        );
+    }
+
+    #[test]
+    fn test_security_rules() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+key "legacy" { algorithm hmac-md5; secret "c2VjcmV0"; };
+options {
+    version none;
+    querylog yes;
+    dnssec-validation no;
+};
+controls {
+    inet 0.0.0.0 allow { any; };
+    inet 127.0.0.1 allow { localhost; };
+};
+zone "example.com" {
+    type primary;
+    file "db.example.com";
+    allow-update { 192.0.2.0/24; };
+};
+"#,
+            )
+            .unwrap();
+
+        let diagnostics = lint_conf(obj.root(), Path::new("named.conf"));
+        let codes = diagnostics
+            .iter()
+            .map(|d| d.code.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                "update-by-address",
+                "hmac-md5-key",
+                "dnssec-validation-disabled",
+                "missing-rate-limit",
+                "control-without-keys"
+            ]
+        );
+        assert_eq!(diagnostics[0].start.line + 1, 15);
+        assert_eq!(diagnostics[1].start.line + 1, 2);
+        assert_eq!(
+            diagnostics[4].message,
+            "the control channel on 0.0.0.0 is not loopback and has no keys"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+pub mod conf;
+pub mod consistency;
+pub mod lint;
+pub mod paths;
+pub mod suppress;
+pub mod zone_file;
+pub mod zones;
@@ -0,0 +1,124 @@
+//! Directories a configuration refers to, which `named` needs to exist.
+
+use std::path::PathBuf;
+
+use crate::bind::obj::ObjRef;
+use crate::path::PathResolver;
+
+/// A directory clause such as `key-directory "keys";`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigPath {
+    pub clause: &'static str,
+    /// The path as written.
+    pub path: String,
+    /// The path resolved to a directory on this machine.
+    pub resolved: PathBuf,
+    pub conf_file: Option<PathBuf>,
+    /// 1-based, as BIND reports lines.
+    pub line: u32,
+}
+
+/// `key-directory` and `managed-keys-directory` in `options`, views and
+/// zones, and the `directory` of each `key-store`.
+pub fn config_paths(root: ObjRef, resolver: &PathResolver) -> Vec<ConfigPath> {
+    let mut paths = vec![];
+
+    let mut maps = vec![];
+    maps.extend(root.map_get("options"));
+    let views = root.map_get("view").map(|v| v.list()).unwrap_or_default();
+    maps.extend(views.iter().filter_map(|v| v.tuple_get("options")));
+    for map in maps {
+        add(&mut paths, resolver, map, "key-directory");
+        add(&mut paths, resolver, map, "managed-keys-directory");
+    }
+
+    let mut scopes = vec![root];
+    scopes.extend(views.iter().filter_map(|v| v.tuple_get("options")));
+    for scope in scopes {
+        for zone in scope.map_get("zone").map(|z| z.list()).unwrap_or_default() {
+            if let Some(options) = zone.tuple_get("options") {
+                add(&mut paths, resolver, options, "key-directory");
+            }
+        }
+    }
+
+    for store in root
+        .map_get("key-store")
+        .map(|s| s.list())
+        .unwrap_or_default()
+    {
+        add(&mut paths, resolver, store, "directory");
+    }
+
+    paths
+}
+
+/// The directory clauses whose directories do not exist.
+pub fn missing_paths(root: ObjRef, resolver: &PathResolver) -> Vec<ConfigPath> {
+    config_paths(root, resolver)
+        .into_iter()
+        .filter(|p| !p.resolved.is_dir())
+        .collect()
+}
+
+fn add(paths: &mut Vec<ConfigPath>, resolver: &PathResolver, map: ObjRef, clause: &'static str) {
+    let Some(value) = map.map_get(clause) else {
+        return;
+    };
+    let Some(path) = value.as_str() else {
+        return;
+    };
+
+    paths.push(ConfigPath {
+        clause,
+        path: path.to_string(),
+        resolved: resolver.resolve(path),
+        conf_file: value.file().map(PathBuf::from),
+        line: value.line(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+
+    #[test]
+    fn test_config_paths() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+options { directory "/var/named"; key-directory "keys"; };
+key-store "hsm" { directory "/var/lib/keys"; };
+zone "example.com" { type primary; file "db"; key-directory "/etc/bind/example"; };
+"#,
+            )
+            .unwrap();
+
+        let resolver = PathResolver::new(Path::new("/repo/named.conf"))
+            .with_chroot("/repo/root")
+            .with_config(obj.root());
+        let paths = config_paths(obj.root(), &resolver)
+            .into_iter()
+            .map(|p| (p.clause, p.resolved))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            paths,
+            vec![
+                ("key-directory", PathBuf::from("/repo/root/var/named/keys")),
+                (
+                    "key-directory",
+                    PathBuf::from("/repo/root/etc/bind/example")
+                ),
+                ("directory", PathBuf::from("/repo/root/var/lib/keys")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,238 @@
+//! Comments that silence diagnostics of a rule:
+//!
+//! ```text
+//! allow-transfer { any; };  // bls-ignore: allow-transfer-any
+//! /* bls-ignore-next-line open-recursion, version-not-hidden */
+//! www  60  IN  A  192.0.2.1  ; bls-ignore mixed-rrset-ttl
+//! ```
+//!
+//! `bls-ignore` applies to the line it is on and `bls-ignore-next-line` to
+//! the line after the comment. Without rule IDs every rule is silenced.
+//! BIND's own messages have no rule and cannot be silenced.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::lsp::{document::DocumentKind, path_to_uri};
+use crate::syntax::{conf, zone, Pos};
+
+const UNUSED_SUPPRESSION: &str = "unused-suppression";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suppression {
+    /// The range of the comment.
+    pub start: Pos,
+    pub end: Pos,
+    /// The 0-based line whose diagnostics are silenced.
+    pub line: u32,
+    /// The rule IDs, or none for every rule.
+    pub rules: Vec<String>,
+}
+
+impl Suppression {
+    fn matches(&self, diagnostic: &Diagnostic) -> Option<&str> {
+        let code = diagnostic.code.as_deref()?;
+        if diagnostic.start.line != self.line {
+            return None;
+        }
+        if self.rules.is_empty() {
+            return Some("");
+        }
+
+        self.rules
+            .iter()
+            .find(|rule| *rule == code)
+            .map(|r| r.as_str())
+    }
+}
+
+/// The suppressions in the comments of a configuration or zone file.
+pub fn read_suppressions(text: &str, kind: DocumentKind) -> Vec<Suppression> {
+    let comments = match kind {
+        DocumentKind::NamedConf => conf::lex(text)
+            .into_iter()
+            .filter(|t| t.kind == conf::TokenKind::Comment)
+            .map(|t| (t.text, t.start, t.end))
+            .collect::<Vec<_>>(),
+        DocumentKind::Zone => zone::lex(text)
+            .into_iter()
+            .filter(|t| t.kind == zone::TokenKind::Comment)
+            .map(|t| (t.text, t.start, t.end))
+            .collect(),
+    };
+
+    comments
+        .into_iter()
+        .filter_map(|(text, start, end)| parse_comment(&text, start, end))
+        .collect()
+}
+
+fn parse_comment(text: &str, start: Pos, end: Pos) -> Option<Suppression> {
+    let body = match text.strip_prefix("/*") {
+        Some(body) => body.strip_suffix("*/").unwrap_or(body),
+        None => text.trim_start_matches(['/', '#', ';']),
+    };
+    let body = body.trim();
+
+    let (line, rest) = if let Some(rest) = body.strip_prefix("bls-ignore-next-line") {
+        (end.line + 1, rest)
+    } else if let Some(rest) = body.strip_prefix("bls-ignore") {
+        (start.line, rest)
+    } else {
+        return None;
+    };
+    // bls-ignored and the like are not directives
+    if rest.starts_with(|c: char| c != ':' && !c.is_whitespace()) {
+        return None;
+    }
+
+    let rules = rest
+        .trim_start_matches(':')
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|rule| !rule.is_empty())
+        .map(String::from)
+        .collect();
+
+    Some(Suppression {
+        start,
+        end,
+        line,
+        rules,
+    })
+}
+
+/// Drops the diagnostics silenced by comments in their files, and warns
+/// about the suppressions in `path` that silence nothing. Other files
+/// diagnostics are in, such as includes, are read from disk.
+pub fn apply_suppressions(
+    diagnostics: Vec<Diagnostic>,
+    path: &Path,
+    text: &str,
+) -> Vec<Diagnostic> {
+    let mut files: HashMap<PathBuf, Vec<Suppression>> = HashMap::new();
+    files.insert(path.to_path_buf(), read_file(path, text));
+
+    let mut used = HashSet::new();
+    let mut kept = vec![];
+    for diagnostic in diagnostics {
+        if diagnostic.code.is_none() {
+            kept.push(diagnostic);
+            continue;
+        }
+
+        let suppressions = files.entry(diagnostic.file.clone()).or_insert_with(|| {
+            let text = fs::read_to_string(&diagnostic.file).unwrap_or_default();
+            read_file(&diagnostic.file, &text)
+        });
+        let found = suppressions
+            .iter()
+            .enumerate()
+            .find_map(|(i, s)| Some((i, s.matches(&diagnostic)?.to_string())));
+        match found {
+            Some((i, rule)) => {
+                used.insert((diagnostic.file.clone(), i, rule));
+            }
+            None => kept.push(diagnostic),
+        }
+    }
+
+    for (i, suppression) in files[path].iter().enumerate() {
+        let is_used = |rule: &str| used.contains(&(path.to_path_buf(), i, rule.to_string()));
+        let unused = if suppression.rules.is_empty() {
+            if is_used("") {
+                continue;
+            }
+            "bls-ignore suppresses nothing".to_string()
+        } else {
+            let rules = suppression
+                .rules
+                .iter()
+                .filter(|rule| !is_used(rule))
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            if rules.is_empty() {
+                continue;
+            }
+            format!("bls-ignore of {} suppresses nothing", rules.join(", "))
+        };
+
+        kept.push(Diagnostic::new(
+            path,
+            suppression.start,
+            suppression.end,
+            Severity::Warning,
+            UNUSED_SUPPRESSION,
+            unused,
+        ));
+    }
+
+    kept
+}
+
+fn read_file(path: &Path, text: &str) -> Vec<Suppression> {
+    let kind = match path_to_uri(path) {
+        Some(uri) => DocumentKind::detect(&uri, None, text),
+        None => DocumentKind::NamedConf,
+    };
+
+    read_suppressions(text, kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_suppressions() {
+        let text = "options {\n\
+                    \x20   allow-transfer { any; }; // bls-ignore: allow-transfer-any\n\
+                    \x20   /* bls-ignore-next-line open-recursion, version-not-hidden */\n\
+                    \x20   allow-recursion { any; };\n\
+                    };\n";
+        let path = Path::new("named.conf");
+        let line = |line, code: &str| {
+            Diagnostic::new(
+                path,
+                Pos { line, column: 0 },
+                Pos { line, column: 0 },
+                Severity::Warning,
+                code,
+                String::new(),
+            )
+        };
+
+        let diagnostics = vec![
+            line(1, "allow-transfer-any"),
+            line(3, "open-recursion"),
+            line(3, "open-update"),
+        ];
+        let kept = apply_suppressions(diagnostics, path, text)
+            .into_iter()
+            .map(|d| (d.start.line, d.code.unwrap(), d.message))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            kept,
+            vec![
+                (3, "open-update".to_string(), String::new()),
+                (
+                    2,
+                    UNUSED_SUPPRESSION.to_string(),
+                    "bls-ignore of version-not-hidden suppresses nothing".to_string()
+                ),
+            ]
+        );
+
+        let zone = read_suppressions(
+            "www 60 IN A 192.0.2.1 ; bls-ignore mixed-rrset-ttl\n; bls-ignored\n",
+            DocumentKind::Zone,
+        );
+        assert_eq!(zone.len(), 1);
+        assert_eq!(zone[0].line, 0);
+        assert_eq!(zone[0].rules, vec!["mixed-rrset-ttl".to_string()]);
+    }
+}
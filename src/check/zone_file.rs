@@ -0,0 +1,169 @@
+//! Checks of a single zone file, loaded on its own.
+
+use std::path::Path;
+
+use crate::{
+    bind::zone::{DnsZone, ZoneOptions},
+    diagnostic::{from_bind_log, Diagnostic, Severity},
+    dns::lint::{lint_zone, LintOptions},
+    syntax::{zone, Pos},
+};
+
+/// Loads a zone file through `DnsZone` with `zone_options` and reports what
+/// BIND logs, followed by the SOA timer and TTL lints. The `max-zone-ttl`
+/// lint only runs when there is no origin to load the zone with, as BIND
+/// enforces the limit itself when it loads the zone.
+pub fn check_zone_file(
+    path: &Path,
+    text: &str,
+    zone_options: &ZoneOptions,
+    lint_options: &LintOptions,
+) -> Vec<Diagnostic> {
+    let loaded = zone_origin(path, text)
+        .and_then(|origin| load_diagnostics(path, &origin, text, zone_options));
+    let (mut diagnostics, lint_options) = match loaded {
+        Some(diagnostics) => (diagnostics, LintOptions { max_zone_ttl: None }),
+        None => (vec![], lint_options.clone()),
+    };
+
+    diagnostics.extend(lint_zone(text, &lint_options).into_iter().map(|lint| {
+        Diagnostic::new(
+            path,
+            lint.start,
+            lint.end,
+            Severity::Warning,
+            lint.code,
+            lint.message,
+        )
+    }));
+
+    diagnostics
+}
+
+/// What BIND logs loading the zone, or `None` when an origin it rejects
+/// leaves nothing to load.
+fn load_diagnostics(
+    path: &Path,
+    origin: &str,
+    text: &str,
+    options: &ZoneOptions,
+) -> Option<Vec<Diagnostic>> {
+    let zone = DnsZone::new();
+    let log = zone.check_with(origin, text, options).ok()?;
+
+    Some(zone_log_diagnostics(&log, path))
+}
+
+/// The diagnostics of what BIND logged while loading a zone, leaving out
+/// the line announcing the loaded serial.
+pub fn zone_log_diagnostics(log: &str, path: &Path) -> Vec<Diagnostic> {
+    let log = log
+        .lines()
+        .filter(|line| !line.contains("loaded serial"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    from_bind_log(&log, path)
+}
+
+/// The origin of a zone file: its leading `$ORIGIN`, an absolute SOA owner,
+/// or a domain name in the file name such as `db.example.com` or
+/// `example.com.zone`.
+pub fn zone_origin(path: &Path, text: &str) -> Option<String> {
+    let parsed = zone::parse(text, None);
+    let first_record = parsed.records.first().map(|r| r.start);
+
+    let leading = |pos: Pos| first_record.is_none_or(|first| pos < first);
+    let directive = parsed
+        .directives
+        .iter()
+        .find(|d| d.name.text.eq_ignore_ascii_case("$ORIGIN") && leading(d.start));
+    if let Some(name) = directive.and_then(|d| d.args.first()) {
+        return Some(name.value().to_string());
+    }
+
+    let soa = parsed.records.iter().find(|r| r.rtype == "SOA");
+    if let Some(owner) = soa.map(|r| &r.owner).filter(|o| o.ends_with('.')) {
+        return Some(owner.clone());
+    }
+
+    let name = path.file_name()?.to_string_lossy().to_ascii_lowercase();
+    let name = name.strip_prefix("db.").unwrap_or(&name);
+    let name = [".zone", ".db", ".hosts"]
+        .iter()
+        .find_map(|ext| name.strip_suffix(ext))
+        .unwrap_or(name);
+
+    name.contains('.').then(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_origin() {
+        let origin = |path: &str, text: &str| zone_origin(Path::new(path), text);
+
+        assert_eq!(
+            origin(
+                "zones/a",
+                "$ORIGIN example.org.\n@ IN SOA ns admin 1 2 3 4 5\n"
+            ),
+            Some("example.org.".to_string())
+        );
+        assert_eq!(
+            origin("zones/a", "example.net. IN SOA ns admin 1 2 3 4 5\n"),
+            Some("example.net.".to_string())
+        );
+        assert_eq!(
+            origin("zones/db.example.com", "@ IN SOA ns admin 1 2 3 4 5\n"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            origin("zones/example.com.zone", "@ IN SOA ns admin 1 2 3 4 5\n"),
+            Some("example.com".to_string())
+        );
+        assert_eq!(
+            origin("zones/a.zone", "@ IN SOA ns admin 1 2 3 4 5\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_max_zone_ttl() {
+        let text = "$ORIGIN example.com.\n\
+                    $TTL 1h\n\
+                    @ IN SOA ns admin 1 3600 1800 604800 3600\n\
+                    @ IN NS ns\n\
+                    ns IN A 192.0.2.1\n\
+                    big 1w IN TXT \"x\"\n";
+        let zone_options = ZoneOptions {
+            max_zone_ttl: 86400,
+            ..Default::default()
+        };
+        let lint_options = LintOptions {
+            max_zone_ttl: Some(86400),
+        };
+
+        // reported once, by BIND, which refuses to load the zone
+        let diagnostics =
+            check_zone_file(Path::new("db.example"), text, &zone_options, &lint_options);
+        let exceeding = diagnostics
+            .iter()
+            .filter(|d| d.message.contains("max-zone-ttl"))
+            .collect::<Vec<_>>();
+        assert_eq!(exceeding.len(), 1);
+        assert_eq!(exceeding[0].source, "bind");
+        assert_eq!(exceeding[0].start.line, 5);
+
+        // without an origin nothing is loaded, and the lint reports it
+        let text = text.replace("$ORIGIN example.com.\n", "");
+        let diagnostics = check_zone_file(Path::new("zone"), &text, &zone_options, &lint_options);
+        let codes = diagnostics
+            .iter()
+            .filter_map(|d| d.code.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(codes, vec!["ttl-exceeds-max-zone-ttl"]);
+    }
+}
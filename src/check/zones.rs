@@ -0,0 +1,313 @@
+use std::path::PathBuf;
+
+use crate::bind::{
+    obj::ObjRef,
+    zone::{CheckMode, DnsZone, MasterFormat, ZoneOptions},
+};
+use crate::dns::record::Record;
+use crate::path::PathResolver;
+
+/// A `zone` statement of a parsed configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneConfig {
+    pub name: String,
+    pub view: Option<String>,
+    pub zone_type: String,
+    /// The `file` clause, resolved to a file on this machine.
+    pub file: Option<PathBuf>,
+    /// Where the statement is, with a 1-based line.
+    pub conf_file: Option<PathBuf>,
+    pub line: u32,
+    /// `masterfile-format`, which defaults to raw for secondaries.
+    pub format: MasterFormat,
+    /// The zone options, inherited from the view and `options`.
+    pub options: ZoneOptions,
+}
+
+impl ZoneConfig {
+    /// Whether this is a primary zone. `master` is the old spelling.
+    pub fn is_primary(&self) -> bool {
+        matches!(self.zone_type.as_str(), "primary" | "master")
+    }
+
+    /// Whether this is a secondary zone. `slave` is the old spelling.
+    pub fn is_secondary(&self) -> bool {
+        matches!(self.zone_type.as_str(), "secondary" | "slave")
+    }
+}
+
+/// The outcome of loading one configured zone.
+#[derive(Debug, Clone)]
+pub struct ZoneCheck {
+    pub zone: ZoneConfig,
+    /// The loaded serial, or `None` when the zone failed to load.
+    pub serial: Option<u32>,
+    /// What BIND logged while loading the zone.
+    pub log: String,
+    /// The records of the loaded zone, empty when it failed to load.
+    pub records: Vec<Record>,
+}
+
+/// Every `zone` statement of a configuration, including the ones in views,
+/// with `file` paths resolved through `resolver`.
+pub fn zone_configs(root: ObjRef, resolver: &PathResolver) -> Vec<ZoneConfig> {
+    let global = root.map_get("options");
+
+    let mut zones = vec![];
+    collect(root, None, &[global], resolver, &mut zones);
+    for view in root.map_get("view").map(|v| v.list()).unwrap_or_default() {
+        let name = view.tuple_get("name").and_then(|n| n.as_str());
+        if let Some(options) = view.tuple_get("options") {
+            collect(
+                options,
+                name,
+                &[Some(options), global],
+                resolver,
+                &mut zones,
+            );
+        }
+    }
+
+    zones
+}
+
+/// Loads every primary zone of a configuration, and with
+/// `include_secondaries` every secondary zone whose file exists, the way
+/// `named-checkconf -z` does.
+pub fn check_all_zones(
+    root: ObjRef,
+    resolver: &PathResolver,
+    include_secondaries: bool,
+) -> Vec<ZoneCheck> {
+    let mut checks = vec![];
+    for zone in zone_configs(root, resolver) {
+        let Some(file) = &zone.file else {
+            continue;
+        };
+        let wanted =
+            zone.is_primary() || (include_secondaries && zone.is_secondary() && file.exists());
+        if !wanted {
+            continue;
+        }
+
+        let (serial, log, records) =
+            match DnsZone::load_file(&zone.name, file, zone.format, &zone.options) {
+                Ok(loaded) => (
+                    loaded.serial(),
+                    loaded.log(),
+                    loaded.records().unwrap_or_default(),
+                ),
+                Err(err) => (None, err.to_string(), vec![]),
+            };
+        checks.push(ZoneCheck {
+            zone,
+            serial,
+            log,
+            records,
+        });
+    }
+
+    checks
+}
+
+/// Collects the zones of `map`. `scopes` are the view and global options
+/// the zones inherit from, nearest first.
+fn collect(
+    map: ObjRef,
+    view: Option<&str>,
+    scopes: &[Option<ObjRef>],
+    resolver: &PathResolver,
+    zones: &mut Vec<ZoneConfig>,
+) {
+    for zone in map.map_get("zone").map(|z| z.list()).unwrap_or_default() {
+        let Some(name) = zone.tuple_get("name").and_then(|n| n.as_str()) else {
+            continue;
+        };
+        let options = zone.tuple_get("options");
+        let clause = |name: &str| {
+            options
+                .and_then(|o| o.map_get(name))
+                .and_then(|v| v.as_str())
+        };
+
+        let zone_type = clause("type").unwrap_or_default().to_ascii_lowercase();
+        let secondary = matches!(zone_type.as_str(), "secondary" | "slave");
+        let scopes = [options]
+            .into_iter()
+            .chain(scopes.iter().copied())
+            .collect::<Vec<_>>();
+        let format = lookup(&scopes, "masterfile-format")
+            .and_then(|f| f.as_str())
+            .and_then(MasterFormat::parse)
+            .unwrap_or(if secondary {
+                MasterFormat::Raw
+            } else {
+                MasterFormat::Text
+            });
+
+        zones.push(ZoneConfig {
+            name: name.to_string(),
+            view: view.map(str::to_string),
+            file: clause("file").map(|f| resolver.resolve(f)),
+            conf_file: zone.file().map(PathBuf::from),
+            line: zone.line(),
+            format,
+            options: zone_options(&scopes, secondary),
+            zone_type,
+        });
+    }
+}
+
+/// The first of `scopes` that sets the clause `name`.
+fn lookup<'a>(scopes: &[Option<ObjRef<'a>>], name: &str) -> Option<ObjRef<'a>> {
+    scopes
+        .iter()
+        .flatten()
+        .find_map(|scope| scope.map_get(name))
+}
+
+/// Resolves the options `named` applies when loading a zone, falling back
+/// to its defaults: `check-names` fails on primaries and warns on
+/// secondaries.
+fn zone_options(scopes: &[Option<ObjRef>], secondary: bool) -> ZoneOptions {
+    let mut options = ZoneOptions {
+        check_names: if secondary {
+            CheckMode::Warn
+        } else {
+            CheckMode::Fail
+        },
+        ..Default::default()
+    };
+
+    let mode = |name: &str| {
+        lookup(scopes, name)
+            .and_then(|v| v.as_str())
+            .and_then(CheckMode::parse)
+    };
+    let flag = |name: &str| lookup(scopes, name).and_then(|v| v.as_bool());
+
+    // check-names is a mode in a zone and a list of (type, mode) pairs in
+    // views and options, so the scopes are searched for a matching entry
+    let check_names = scopes.iter().flatten().find_map(|scope| {
+        let value = scope.map_get("check-names")?;
+        if let Some(mode) = value.as_str() {
+            return CheckMode::parse(mode);
+        }
+        value.list().into_iter().find_map(|entry| {
+            let kind = entry.tuple_get("type")?.as_str()?.to_ascii_lowercase();
+            let applies = match kind.as_str() {
+                "primary" | "master" => !secondary,
+                "secondary" | "slave" => secondary,
+                _ => false,
+            };
+            if !applies {
+                return None;
+            }
+            CheckMode::parse(entry.tuple_get("mode")?.as_str()?)
+        })
+    });
+    if let Some(mode) = check_names {
+        options.check_names = mode;
+    }
+
+    if let Some(mode) = mode("check-mx") {
+        options.check_mx = mode;
+    }
+    if let Some(mode) = mode("check-dup-records") {
+        options.check_dup_records = mode;
+    }
+    if let Some(mode) = mode("check-mx-cname") {
+        options.check_mx_cname = mode;
+    }
+    if let Some(mode) = mode("check-srv-cname") {
+        options.check_srv_cname = mode;
+    }
+    if let Some(mode) = mode("check-spf") {
+        options.check_spf = mode != CheckMode::Ignore;
+    }
+    if let Some(enabled) = flag("check-integrity") {
+        options.check_integrity = enabled;
+    }
+    if let Some(enabled) = flag("check-wildcard") {
+        options.check_wildcard = enabled;
+    }
+    if let Some(enabled) = flag("check-sibling") {
+        options.check_sibling = enabled;
+    }
+    if let Some(enabled) = flag("check-svcb") {
+        options.check_svcb = enabled;
+    }
+    if let Some(ttl) = lookup(scopes, "max-zone-ttl").and_then(|v| v.as_duration()) {
+        options.max_zone_ttl = ttl;
+    }
+
+    options
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::bind::{log::IscLog, mem::IscMem, parser::IscParser};
+
+    #[test]
+    fn test_zone_configs() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+options { directory "zones"; check-names primary warn; check-mx fail; };
+zone "example.com" { type master; file "db.example.com"; check-sibling no; };
+view "internal" {
+    zone "corp.example" { type secondary; file "/var/cache/corp.example.db"; primaries { 192.0.2.1; }; };
+};
+"#,
+            )
+            .unwrap();
+
+        let resolver = PathResolver::new(Path::new("/etc/bind/named.conf")).with_config(obj.root());
+        let zones = zone_configs(obj.root(), &resolver);
+        assert_eq!(zones[0].format, MasterFormat::Text);
+        assert_eq!(zones[0].options.check_names, CheckMode::Warn);
+        assert_eq!(zones[0].options.check_mx, CheckMode::Fail);
+        assert!(!zones[0].options.check_sibling);
+        assert_eq!(zones[1].format, MasterFormat::Raw);
+        assert_eq!(zones[1].options.check_names, CheckMode::Warn);
+        assert_eq!(zones[1].options.check_mx, CheckMode::Fail);
+        assert!(zones[1].options.check_sibling);
+
+        let zones = zones
+            .iter()
+            .map(|z| {
+                (
+                    z.name.as_str(),
+                    z.view.as_deref(),
+                    z.is_primary(),
+                    z.file.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            zones,
+            vec![
+                (
+                    "example.com",
+                    None,
+                    true,
+                    Some(PathBuf::from("/etc/bind/zones/db.example.com"))
+                ),
+                (
+                    "corp.example",
+                    Some("internal"),
+                    false,
+                    Some(PathBuf::from("/var/cache/corp.example.db"))
+                ),
+            ]
+        );
+    }
+}
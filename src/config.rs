@@ -0,0 +1,312 @@
+//! Project configuration, read from a `.bls.toml` in the workspace, with
+//! the client's `workspace/didChangeConfiguration` settings applied over it.
+//!
+//! ```toml
+//! # relative paths are relative to this file
+//! roots = ["dns/prod/etc/bind/named.conf"]
+//! chroot = "dns/prod"
+//!
+//! # deployed path = path in the checkout
+//! [paths]
+//! "/etc/bind" = "dns/prod/etc/bind"
+//!
+//! [check]
+//! plugins = false
+//!
+//! [zone]
+//! check-names = "fail"
+//! max-zone-ttl = 86400
+//!
+//! [lints]
+//! mixed-rrset-ttl = "error"
+//! soa-ncache-ttl = "off"
+//! version-not-hidden = "off"
+//!
+//! [format]
+//! use-tabs = false
+//! indent-width = 4
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::bind::{obj::CheckFlags, zone::ZoneOptions};
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::dns::lint::LintOptions;
+use crate::path::PathResolver;
+
+pub const CONFIG_FILE: &str = ".bls.toml";
+
+/// The severity configured for a lint rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleSeverity {
+    /// The rule is not reported.
+    Off,
+    #[serde(untagged)]
+    On(Severity),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct FormatOptions {
+    /// Indent with tabs rather than spaces. Unset follows the editor.
+    pub use_tabs: Option<bool>,
+    /// Spaces per level when indenting with spaces. Unset follows the
+    /// editor.
+    pub indent_width: Option<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ProjectConfig {
+    /// The root configuration files. Without any, every `named.conf` in
+    /// the workspace is a root.
+    pub roots: Vec<PathBuf>,
+    /// The checkout's copy of the directory `named` is chrooted to.
+    pub chroot: Option<PathBuf>,
+    /// Prefixes of deployed paths mapped to the checkout.
+    pub paths: BTreeMap<PathBuf, PathBuf>,
+    /// The optional checks of the configuration.
+    pub check: CheckFlags,
+    /// The options zone files opened on their own are checked with.
+    pub zone: ZoneOptions,
+    /// Severities of lint rules by code.
+    pub lints: BTreeMap<String, RuleSeverity>,
+    pub format: FormatOptions,
+    /// The directory of the config file, that relative paths are taken
+    /// relative to.
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+impl ProjectConfig {
+    pub fn parse(text: &str, root: &Path) -> Result<Self, Box<dyn Error>> {
+        let mut config: ProjectConfig = toml::from_str(text)?;
+        config.root = root.to_path_buf();
+
+        Ok(config)
+    }
+
+    /// Parses a `.bls.toml` with the `bls` section of the settings sent by
+    /// the client applied over it. Settings without one are ignored, as
+    /// clients may send the settings of every extension. Tables are merged
+    /// key by key, so settings with only `lints` keep the file's `roots`,
+    /// `chroot` and `paths`, while other values replace the file's.
+    pub fn parse_with_settings(
+        text: &str,
+        settings: &serde_json::Value,
+        root: &Path,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut value = serde_json::to_value(toml::from_str::<toml::Table>(text)?)?;
+        if let Some(settings) = settings.get("bls") {
+            merge(&mut value, settings);
+        }
+
+        let mut config: ProjectConfig = serde_json::from_value(value)?;
+        config.root = root.to_path_buf();
+
+        Ok(config)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::load_with_settings(path, &serde_json::Value::Null)
+    }
+
+    fn load_with_settings(
+        path: &Path,
+        settings: &serde_json::Value,
+    ) -> Result<Self, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+        let root = path.parent().unwrap_or(Path::new("."));
+
+        ProjectConfig::parse_with_settings(&text, settings, root)
+            .map_err(|err| format!("{}: {}", path.display(), err).into())
+    }
+
+    /// Finds the `.bls.toml` in `dir` or the closest of its ancestors.
+    /// Having none is not an error, but a broken one is.
+    pub fn discover(dir: &Path) -> Result<Self, Box<dyn Error>> {
+        Self::discover_with_settings(dir, &serde_json::Value::Null)
+    }
+
+    /// Like [`ProjectConfig::discover`], with settings sent by the client
+    /// applied as in [`ProjectConfig::parse_with_settings`]. Relative paths
+    /// are relative to the `.bls.toml`, or to `dir` without one.
+    pub fn discover_with_settings(
+        dir: &Path,
+        settings: &serde_json::Value,
+    ) -> Result<Self, Box<dyn Error>> {
+        // the parent of a bare file name is empty
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+        for ancestor in dir.ancestors() {
+            let path = ancestor.join(CONFIG_FILE);
+            if path.is_file() {
+                return ProjectConfig::load_with_settings(&path, settings);
+            }
+        }
+
+        ProjectConfig::parse_with_settings("", settings, &dir)
+            .map_err(|err| format!("client settings: {}", err).into())
+    }
+
+    /// The root configuration files as absolute paths.
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.roots.iter().map(|root| self.root.join(root)).collect()
+    }
+
+    /// The path mappings with workspace paths made absolute.
+    pub fn mappings(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.paths
+            .iter()
+            .map(|(deployed, local)| (deployed.clone(), self.root.join(local)))
+            .collect()
+    }
+
+    /// A resolver for the configuration at `conf_path` that applies the
+    /// chroot and path mappings.
+    pub fn resolver(&self, conf_path: &Path) -> PathResolver {
+        let resolver = PathResolver::new(conf_path).with_mappings(self.mappings());
+        match &self.chroot {
+            Some(chroot) => resolver.with_chroot(self.root.join(chroot)),
+            None => resolver,
+        }
+    }
+
+    pub fn lint_options(&self) -> LintOptions {
+        LintOptions {
+            max_zone_ttl: (self.zone.max_zone_ttl != 0).then_some(self.zone.max_zone_ttl),
+        }
+    }
+
+    /// The configured severity of a lint rule, if any.
+    pub fn severity(&self, code: &str) -> Option<RuleSeverity> {
+        self.lints.get(code).copied()
+    }
+
+    /// Applies the configured severities to the diagnostics with a code,
+    /// dropping those of rules that are turned off.
+    pub fn apply_severities(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                match diagnostic.code.as_deref().and_then(|c| self.severity(c)) {
+                    Some(RuleSeverity::Off) => return None,
+                    Some(RuleSeverity::On(severity)) => diagnostic.severity = severity,
+                    None => {}
+                }
+
+                Some(diagnostic)
+            })
+            .collect()
+    }
+}
+
+/// Merges `settings` into `value`, table by table. Anything but a table
+/// replaces what it is merged into, and settings that are not a table are
+/// ignored.
+fn merge(value: &mut serde_json::Value, settings: &serde_json::Value) {
+    let (serde_json::Value::Object(value), serde_json::Value::Object(settings)) = (value, settings)
+    else {
+        return;
+    };
+
+    for (key, setting) in settings {
+        match value.get_mut(key) {
+            Some(existing) if existing.is_object() && setting.is_object() => {
+                merge(existing, setting)
+            }
+            _ => {
+                value.insert(key.clone(), setting.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bind::zone::CheckMode;
+
+    #[test]
+    fn test_parse() {
+        let text = "[paths]\n\"/etc/bind\" = \"dns/prod/etc/bind\"\n";
+        let config = ProjectConfig::parse(text, Path::new("/repo")).unwrap();
+        assert_eq!(
+            config.mappings(),
+            vec![(
+                PathBuf::from("/etc/bind"),
+                PathBuf::from("/repo/dns/prod/etc/bind")
+            )]
+        );
+
+        let resolver = config.resolver(Path::new("/repo/dns/prod/etc/bind/named.conf"));
+        assert_eq!(
+            resolver.resolve("/etc/bind/named.conf.local"),
+            Path::new("/repo/dns/prod/etc/bind/named.conf.local")
+        );
+
+        assert!(ProjectConfig::parse("[path]\n", Path::new("/repo")).is_err());
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        let text = "roots = [\"etc/named.conf\"]\n\
+                    chroot = \"root\"\n\
+                    [check]\nplugins = false\n\
+                    [zone]\ncheck-names = \"fail\"\nmax-zone-ttl = 3600\n\
+                    [lints]\nmixed-rrset-ttl = \"off\"\n\
+                    [format]\nuse-tabs = false\n";
+        let config = ProjectConfig::parse(text, Path::new("/repo")).unwrap();
+
+        assert_eq!(config.roots(), vec![PathBuf::from("/repo/etc/named.conf")]);
+        assert_eq!(
+            config.resolver(Path::new("/repo/etc/named.conf")).chroot(),
+            Some(Path::new("/repo/root"))
+        );
+        assert!(!config.check.plugins && config.check.algorithms);
+        assert_eq!(config.zone.check_names, CheckMode::Fail);
+        assert_eq!(config.zone.check_mx, CheckMode::Warn);
+        assert_eq!(config.lint_options().max_zone_ttl, Some(3600));
+        assert_eq!(config.severity("mixed-rrset-ttl"), Some(RuleSeverity::Off));
+        assert_eq!(config.format.use_tabs, Some(false));
+
+        let settings = serde_json::json!({ "bls": { "lints": { "soa-ncache-ttl": "hint" } } });
+        let merged =
+            ProjectConfig::parse_with_settings(text, &settings, Path::new("/repo")).unwrap();
+        assert_eq!(
+            merged.severity("soa-ncache-ttl"),
+            Some(RuleSeverity::On(Severity::Hint))
+        );
+        assert_eq!(merged.severity("mixed-rrset-ttl"), Some(RuleSeverity::Off));
+        assert_eq!(merged.roots, config.roots);
+        assert_eq!(merged.chroot, config.chroot);
+        assert_eq!(merged.zone, config.zone);
+
+        let settings = serde_json::json!({ "bls": { "roots": ["named.conf"] } });
+        let merged =
+            ProjectConfig::parse_with_settings(text, &settings, Path::new("/repo")).unwrap();
+        assert_eq!(merged.roots(), vec![PathBuf::from("/repo/named.conf")]);
+
+        // the settings of other extensions are not ours
+        let settings = serde_json::json!({ "roots": ["named.conf"], "editor": { "tabSize": 4 } });
+        let other =
+            ProjectConfig::parse_with_settings(text, &settings, Path::new("/repo")).unwrap();
+        assert_eq!(other, config);
+
+        let unset =
+            ProjectConfig::parse_with_settings(text, &serde_json::Value::Null, Path::new("/repo"));
+        assert_eq!(unset.unwrap(), config);
+    }
+}
@@ -0,0 +1,249 @@
+//! Diagnostics as reported by both the language server and `bls check`.
+
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{DiagnosticSeverity, NumberOrString, TextEdit};
+use serde::Deserialize;
+
+use crate::lsp::range;
+use crate::syntax::Pos;
+
+/// The column of a diagnostic that covers a whole line, as BIND reports
+/// only line numbers.
+pub const LINE_END: u32 = u32::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+    Hint,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Hint => "hint",
+        }
+    }
+}
+
+/// A replacement of the text between two positions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub start: Pos,
+    pub end: Pos,
+    pub new_text: String,
+}
+
+impl Edit {
+    pub fn new((start, end): (Pos, Pos), new_text: &str) -> Self {
+        Edit {
+            start,
+            end,
+            new_text: new_text.to_string(),
+        }
+    }
+
+    pub fn to_lsp(&self) -> TextEdit {
+        TextEdit {
+            range: range(self.start, self.end),
+            new_text: self.new_text.clone(),
+        }
+    }
+}
+
+/// A suggested fix, as edits of the file the diagnostic is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub title: String,
+    pub edits: Vec<Edit>,
+    /// Whether this is the fix to apply when there are several.
+    pub preferred: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    pub start: Pos,
+    pub end: Pos,
+    pub severity: Severity,
+    /// The rule that produced the diagnostic. BIND's own messages have
+    /// none.
+    pub code: Option<String>,
+    /// `bind` for what BIND reports, `bls` for checks of our own.
+    pub source: &'static str,
+    pub message: String,
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    /// A diagnostic of one of our own rules.
+    pub fn new(
+        file: &Path,
+        start: Pos,
+        end: Pos,
+        severity: Severity,
+        code: &str,
+        message: String,
+    ) -> Self {
+        Diagnostic {
+            file: file.to_path_buf(),
+            start,
+            end,
+            severity,
+            code: Some(code.to_string()),
+            source: "bls",
+            message,
+            fixes: vec![],
+        }
+    }
+
+    /// A diagnostic covering the whole of a 0-based line.
+    pub fn line(file: &Path, line: u32, severity: Severity, message: String) -> Self {
+        Diagnostic {
+            file: file.to_path_buf(),
+            start: Pos { line, column: 0 },
+            end: Pos {
+                line,
+                column: LINE_END,
+            },
+            severity,
+            code: None,
+            source: "bind",
+            message,
+            fixes: vec![],
+        }
+    }
+
+    pub fn to_lsp(&self) -> lsp_types::Diagnostic {
+        lsp_types::Diagnostic {
+            range: range(self.start, self.end),
+            severity: Some(match self.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                Severity::Info => DiagnosticSeverity::INFORMATION,
+                Severity::Hint => DiagnosticSeverity::HINT,
+            }),
+            code: self.code.clone().map(NumberOrString::String),
+            source: Some(self.source.to_string()),
+            message: self.message.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+/// `file:line:column: severity: message`, with 1-based lines and columns.
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: {}: {}",
+            self.file.display(),
+            self.start.line + 1,
+            self.start.column + 1,
+            self.severity.name(),
+            self.message
+        )?;
+        if let Some(code) = &self.code {
+            write!(f, " [{}]", code)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The name BIND gives the input of a zone loaded from text.
+const STREAM: &str = "<stream>";
+
+/// Turns BIND log lines such as `error: named.conf:3: unknown option 'foo'`
+/// into diagnostics of the level BIND logged them at. Lines that name no
+/// file, or the text of a zone, are reported in `file`.
+pub fn from_bind_log(log: &str, file: &Path) -> Vec<Diagnostic> {
+    log.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (severity, line) = split_level(line);
+            let (location, message) = split_location(line);
+            let (path, line) = match location {
+                Some((path, line)) if path != Path::new(STREAM) => (path, line),
+                Some((_, line)) => (file, line),
+                None => (file, 1),
+            };
+            let line = line.saturating_sub(1);
+
+            Diagnostic::line(path, line, severity, message.to_string())
+        })
+        .collect()
+}
+
+/// Splits the level `IscLog` prints before each line off it. Lines without
+/// one are failures of our own, reported as errors.
+pub fn split_level(line: &str) -> (Severity, &str) {
+    let Some((level, message)) = line.split_once(": ") else {
+        return (Severity::Error, line);
+    };
+
+    let severity = match level {
+        "critical" | "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "notice" | "info" => Severity::Info,
+        _ if level.starts_with("debug") => Severity::Hint,
+        _ => return (Severity::Error, line),
+    };
+    (severity, message)
+}
+
+/// Splits `source:line: message` log lines.
+fn split_location(line: &str) -> (Option<(&Path, u32)>, &str) {
+    let mut parts = line.splitn(3, ':');
+    if let (Some(source), Some(number), Some(message)) = (parts.next(), parts.next(), parts.next())
+    {
+        if let Ok(number) = number.trim().parse() {
+            return (Some((Path::new(source), number)), message.trim());
+        }
+    }
+
+    (None, line.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_bind_log() {
+        let log = "error: named.conf:3: unknown option 'foo'\n\
+                   error: zone example.com/IN: has no NS records\n\
+                   warning: <stream>:7: unknown RR type 'AX'\n\
+                   info: zone example.com/IN: loaded serial 1\n\
+                   example.com: failed to load zone\n";
+        let diagnostics = from_bind_log(log, Path::new("db.example"));
+
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "named.conf:3:1: error: unknown option 'foo'"
+        );
+        assert_eq!(
+            diagnostics[1].to_string(),
+            "db.example:1:1: error: zone example.com/IN: has no NS records"
+        );
+        assert_eq!(
+            diagnostics[2].to_string(),
+            "db.example:7:1: warning: unknown RR type 'AX'"
+        );
+        assert_eq!(diagnostics[3].severity, Severity::Info);
+        assert_eq!(
+            diagnostics[4].to_string(),
+            "db.example:1:1: error: example.com: failed to load zone"
+        );
+        assert_eq!(diagnostics[1].to_lsp().range.end.character, LINE_END);
+    }
+}
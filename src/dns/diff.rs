@@ -0,0 +1,385 @@
+//! Semantic differences between two versions of a zone.
+//!
+//! Records are grouped into RRsets by owner, class and type, so reordering
+//! records or changing how they are written does not show up as a change.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::{
+    fqdn,
+    record::{RData, Record},
+    serial::next_serial,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RRsetDiff {
+    pub name: String,
+    pub class: String,
+    #[serde(rename = "type")]
+    pub rtype: String,
+    pub kind: ChangeKind,
+    pub old_ttl: Option<u32>,
+    pub new_ttl: Option<u32>,
+    /// Records only in the new version. When the TTL changed this is the
+    /// whole new RRset.
+    pub added: Vec<Record>,
+    /// Records only in the old version. When the TTL changed this is the
+    /// whole old RRset.
+    pub removed: Vec<Record>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ZoneDiff {
+    pub origin: String,
+    pub changes: Vec<RRsetDiff>,
+}
+
+/// Owner (labels reversed, lowercased), class and type, so RRsets sort in
+/// roughly canonical order with the names below each owner.
+type RRsetKey = (Vec<String>, String, String);
+
+fn rrsets(records: &[Record]) -> BTreeMap<RRsetKey, Vec<&Record>> {
+    let mut rrsets: BTreeMap<RRsetKey, Vec<&Record>> = BTreeMap::new();
+    for record in records {
+        let labels = record
+            .name
+            .trim_end_matches('.')
+            .rsplit('.')
+            .map(str::to_ascii_lowercase)
+            .collect();
+        let key = (
+            labels,
+            record.class.to_ascii_uppercase(),
+            record.rtype().to_ascii_uppercase(),
+        );
+        rrsets.entry(key).or_default().push(record);
+    }
+
+    rrsets
+}
+
+impl ZoneDiff {
+    pub fn new(origin: &str, old: &[Record], new: &[Record]) -> Self {
+        let old = rrsets(old);
+        let mut new = rrsets(new);
+        let mut changes = vec![];
+
+        for (key, old_set) in old {
+            let new_set = new.remove(&key).unwrap_or_default();
+            if let Some(change) = RRsetDiff::new(&old_set, &new_set) {
+                changes.push((key, change));
+            }
+        }
+        for (key, new_set) in new {
+            if let Some(change) = RRsetDiff::new(&[], &new_set) {
+                changes.push((key, change));
+            }
+        }
+        changes.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        ZoneDiff {
+            origin: fqdn(origin),
+            changes: changes.into_iter().map(|(_, change)| change).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// One line per record, prefixed with `+` or `-`, and a `~` line for
+    /// each TTL change.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for change in &self.changes {
+            if let (ChangeKind::Changed, Some(old), Some(new)) =
+                (change.kind, change.old_ttl, change.new_ttl)
+            {
+                if old != new {
+                    text.push_str(&format!(
+                        "~ {} {} {} ttl {} -> {}\n",
+                        change.name, change.class, change.rtype, old, new
+                    ));
+                }
+            }
+            for record in &change.removed {
+                text.push_str(&format!("- {}\n", record));
+            }
+            for record in &change.added {
+                text.push_str(&format!("+ {}\n", record));
+            }
+        }
+
+        text
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Add,
+    Delete,
+}
+
+/// An IXFR-style difference: the old SOA and the deleted records, then the
+/// new SOA and the added records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Ixfr {
+    pub origin: String,
+    pub old_soa: Record,
+    pub new_soa: Record,
+    pub deleted: Vec<Record>,
+    pub added: Vec<Record>,
+    /// The new version did not increase the serial, so it was bumped here.
+    pub serial_bumped: bool,
+}
+
+impl Ixfr {
+    /// Builds the difference from `dns_diff` tuples, or `None` when nothing
+    /// changed. A serial that does not increase is replaced by the next one
+    /// in the old serial's scheme, so the change can be transferred.
+    pub fn new(
+        origin: &str,
+        old_soa: Record,
+        tuples: Vec<(DiffOp, Record)>,
+        now: u64,
+    ) -> Option<Self> {
+        let mut new_soa = None;
+        let mut deleted = vec![];
+        let mut added = vec![];
+        for (op, record) in tuples {
+            match (op, &record.data) {
+                (DiffOp::Add, RData::SOA { .. }) => new_soa = Some(record),
+                (DiffOp::Delete, RData::SOA { .. }) => {}
+                (DiffOp::Add, _) => added.push(record),
+                (DiffOp::Delete, _) => deleted.push(record),
+            }
+        }
+        if new_soa.is_none() && deleted.is_empty() && added.is_empty() {
+            return None;
+        }
+
+        let mut new_soa = new_soa.unwrap_or_else(|| old_soa.clone());
+        let mut serial_bumped = false;
+        if let (RData::SOA { serial: old, .. }, RData::SOA { serial: new, .. }) =
+            (&old_soa.data, &mut new_soa.data)
+        {
+            // RFC 1982 serial number arithmetic
+            if (new.wrapping_sub(*old) as i32) <= 0 {
+                *new = next_serial(*old, now);
+                serial_bumped = true;
+            }
+        }
+
+        Some(Ixfr {
+            origin: fqdn(origin),
+            old_soa,
+            new_soa,
+            deleted,
+            added,
+            serial_bumped,
+        })
+    }
+
+    /// The difference in the style of `dns_diff_print`.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("del {}\n", self.old_soa);
+        for record in &self.deleted {
+            text.push_str(&format!("del {}\n", record));
+        }
+        text.push_str(&format!("add {}\n", self.new_soa));
+        for record in &self.added {
+            text.push_str(&format!("add {}\n", record));
+        }
+
+        text
+    }
+
+    /// An nsupdate script applying the difference. The old SOA is not
+    /// deleted, as servers ignore that; adding the new one replaces it.
+    pub fn to_nsupdate(&self) -> String {
+        let mut text = format!("zone {}\n", self.origin);
+        for record in &self.deleted {
+            text.push_str(&format!(
+                "update delete {} {} {} {}\n",
+                record.name,
+                record.class,
+                record.rtype(),
+                record.data
+            ));
+        }
+        text.push_str(&format!("update add {}\n", self.new_soa));
+        for record in &self.added {
+            text.push_str(&format!("update add {}\n", record));
+        }
+        text.push_str("send\n");
+
+        text
+    }
+}
+
+impl RRsetDiff {
+    fn new(old: &[&Record], new: &[&Record]) -> Option<Self> {
+        let first = old.first().or(new.first())?;
+        let old_ttl = old.first().map(|r| r.ttl);
+        let new_ttl = new.first().map(|r| r.ttl);
+
+        let (kind, added, removed) = match (old_ttl, new_ttl) {
+            (None, _) => (ChangeKind::Added, new.to_vec(), vec![]),
+            (_, None) => (ChangeKind::Removed, vec![], old.to_vec()),
+            (Some(old_ttl), Some(new_ttl)) if old_ttl != new_ttl => {
+                (ChangeKind::Changed, new.to_vec(), old.to_vec())
+            }
+            _ => {
+                let added = new
+                    .iter()
+                    .filter(|n| !old.iter().any(|o| o.data == n.data))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let removed = old
+                    .iter()
+                    .filter(|o| !new.iter().any(|n| n.data == o.data))
+                    .copied()
+                    .collect::<Vec<_>>();
+                if added.is_empty() && removed.is_empty() {
+                    return None;
+                }
+
+                (ChangeKind::Changed, added, removed)
+            }
+        };
+
+        Some(RRsetDiff {
+            name: first.name.clone(),
+            class: first.class.clone(),
+            rtype: first.rtype().to_string(),
+            kind,
+            old_ttl,
+            new_ttl,
+            added: added.into_iter().cloned().collect(),
+            removed: removed.into_iter().cloned().collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, ttl: u32, rtype: &str, data: &str) -> Record {
+        Record {
+            name: name.to_string(),
+            ttl,
+            class: "IN".to_string(),
+            data: RData::parse(rtype, data),
+        }
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = vec![
+            record("www.example.com.", 300, "A", "192.0.2.1"),
+            record("www.example.com.", 300, "A", "192.0.2.2"),
+            record("ftp.example.com.", 300, "A", "192.0.2.3"),
+            record("example.com.", 3600, "MX", "10 mail.example.com."),
+        ];
+        let new = vec![
+            record("example.com.", 600, "MX", "10 mail.example.com."),
+            record("www.example.com.", 300, "A", "192.0.2.2"),
+            record("WWW.example.com.", 300, "A", "192.0.2.4"),
+            record("mail.example.com.", 300, "A", "192.0.2.5"),
+        ];
+
+        let diff = ZoneDiff::new("example.com.", &old, &new);
+        let kinds = diff
+            .changes
+            .iter()
+            .map(|c| (c.name.as_str(), c.kind))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                ("example.com.", ChangeKind::Changed),
+                ("ftp.example.com.", ChangeKind::Removed),
+                ("mail.example.com.", ChangeKind::Added),
+                ("www.example.com.", ChangeKind::Changed),
+            ]
+        );
+
+        assert_eq!(
+            diff.to_text(),
+            "~ example.com. IN MX ttl 3600 -> 600\n\
+             - example.com. 3600 IN MX 10 mail.example.com.\n\
+             + example.com. 600 IN MX 10 mail.example.com.\n\
+             - ftp.example.com. 300 IN A 192.0.2.3\n\
+             + mail.example.com. 300 IN A 192.0.2.5\n\
+             - www.example.com. 300 IN A 192.0.2.1\n\
+             + WWW.example.com. 300 IN A 192.0.2.4\n"
+        );
+
+        let reordered = old.iter().rev().cloned().collect::<Vec<_>>();
+        assert!(ZoneDiff::new("example.com.", &old, &reordered).is_empty());
+    }
+
+    #[test]
+    fn test_ixfr() {
+        let soa = |serial: u32| {
+            record(
+                "example.com.",
+                3600,
+                "SOA",
+                &format!("ns. admin. {} 3600 900 604800 300", serial),
+            )
+        };
+        let tuples = vec![
+            (DiffOp::Delete, soa(1)),
+            (
+                DiffOp::Delete,
+                record("www.example.com.", 300, "A", "192.0.2.1"),
+            ),
+            (DiffOp::Add, soa(2)),
+            (
+                DiffOp::Add,
+                record("www.example.com.", 300, "A", "192.0.2.2"),
+            ),
+        ];
+
+        let ixfr = Ixfr::new("example.com", soa(1), tuples, 0).unwrap();
+        assert!(!ixfr.serial_bumped);
+        assert_eq!(
+            ixfr.to_text(),
+            "del example.com. 3600 IN SOA ns. admin. 1 3600 900 604800 300\n\
+             del www.example.com. 300 IN A 192.0.2.1\n\
+             add example.com. 3600 IN SOA ns. admin. 2 3600 900 604800 300\n\
+             add www.example.com. 300 IN A 192.0.2.2\n"
+        );
+        assert_eq!(
+            ixfr.to_nsupdate(),
+            "zone example.com.\n\
+             update delete www.example.com. IN A 192.0.2.1\n\
+             update add example.com. 3600 IN SOA ns. admin. 2 3600 900 604800 300\n\
+             update add www.example.com. 300 IN A 192.0.2.2\n\
+             send\n"
+        );
+
+        let tuples = vec![(
+            DiffOp::Add,
+            record("ftp.example.com.", 300, "A", "192.0.2.3"),
+        )];
+        let ixfr = Ixfr::new("example.com.", soa(1), tuples, 0).unwrap();
+        assert!(ixfr.serial_bumped);
+        assert_eq!(ixfr.new_soa, soa(2));
+
+        assert_eq!(Ixfr::new("example.com.", soa(1), vec![], 0), None);
+    }
+}
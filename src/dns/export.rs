@@ -0,0 +1,92 @@
+//! The JSON form of a whole zone, as produced by `bls zone export` and
+//! accepted by `bls zone import`.
+
+use serde::{Deserialize, Serialize};
+
+use super::{fqdn, record::Record};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneExport {
+    pub origin: String,
+    pub records: Vec<Record>,
+}
+
+impl ZoneExport {
+    pub fn new(origin: &str, records: Vec<Record>) -> Self {
+        ZoneExport {
+            origin: fqdn(origin),
+            records,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// A master file with one record per line and absolute owner names.
+    pub fn to_master_file(&self) -> String {
+        let mut text = format!("$ORIGIN {}\n", fqdn(&self.origin));
+        for record in &self.records {
+            text.push_str(&record.to_string());
+            text.push('\n');
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dns::record::RData;
+
+    #[test]
+    fn test_roundtrip() {
+        let json = r#"{
+  "origin": "example.com",
+  "records": [
+    {
+      "name": "example.com.",
+      "ttl": 3600,
+      "class": "IN",
+      "type": "MX",
+      "data": { "preference": 10, "exchange": "mail.example.com." }
+    },
+    {
+      "name": "www.example.com.",
+      "ttl": 300,
+      "class": "IN",
+      "type": "TXT",
+      "data": "\"hello\" \"world\""
+    },
+    {
+      "name": "x.example.com.",
+      "ttl": 300,
+      "class": "IN",
+      "type": "TYPE65534",
+      "data": "\\# 1 00"
+    }
+  ]
+}"#;
+
+        let zone = ZoneExport::from_json(json).unwrap();
+        assert_eq!(
+            zone.records[1].data,
+            RData::TXT(vec!["hello".to_string(), "world".to_string()])
+        );
+        assert_eq!(
+            zone.to_master_file(),
+            "$ORIGIN example.com.\n\
+             example.com. 3600 IN MX 10 mail.example.com.\n\
+             www.example.com. 300 IN TXT \"hello\" \"world\"\n\
+             x.example.com. 300 IN TYPE65534 \\# 1 00\n"
+        );
+
+        let again = ZoneExport::from_json(&zone.to_json().unwrap()).unwrap();
+        assert_eq!(again, zone);
+    }
+}
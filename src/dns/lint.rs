@@ -0,0 +1,184 @@
+//! Lints for operationally dubious values that `dns_zone_load` accepts.
+
+use std::collections::HashMap;
+
+use crate::syntax::{
+    zone::{self, parse_ttl, Record, Token},
+    Pos,
+};
+
+/// Negative caching TTLs outside this range are reported. RFC 2308
+/// recommends one to three hours, and BIND resolvers cap negative answers
+/// at the three hours of their default `max-ncache-ttl`.
+const NCACHE_TTL_RANGE: (u32, u32) = (60, 10800);
+
+#[derive(Debug, Clone, Default)]
+pub struct LintOptions {
+    /// The `max-zone-ttl` configured for the zone.
+    pub max_zone_ttl: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lint {
+    pub code: &'static str,
+    pub message: String,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Lint {
+    fn new(code: &'static str, token: &Token, message: String) -> Self {
+        Lint {
+            code,
+            message,
+            start: token.start,
+            end: token.end,
+        }
+    }
+}
+
+/// Checks SOA timers and record TTLs of a master file.
+pub fn lint_zone(text: &str, options: &LintOptions) -> Vec<Lint> {
+    let zone = zone::parse(text, None);
+    let mut lints = vec![];
+
+    for record in zone.records.iter().filter(|r| r.rtype == "SOA") {
+        lints.extend(lint_soa(record));
+    }
+
+    if let Some(max) = options.max_zone_ttl {
+        for record in &zone.records {
+            match record.ttl {
+                Some(ttl) if ttl > max => lints.push(Lint::new(
+                    "ttl-exceeds-max-zone-ttl",
+                    ttl_token(record),
+                    format!("TTL {} exceeds max-zone-ttl {}", ttl, max),
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    // RFC 2181 5.2: all records of an RRset must have the same TTL
+    let mut rrsets: HashMap<(String, &str), &Record> = HashMap::new();
+    for record in &zone.records {
+        let key = (record.owner.to_ascii_lowercase(), record.rtype.as_str());
+        let Some(first) = rrsets.get(&key) else {
+            rrsets.insert(key, record);
+            continue;
+        };
+
+        if let (Some(ttl), Some(first_ttl)) = (record.ttl, first.ttl) {
+            if ttl != first_ttl {
+                lints.push(Lint::new(
+                    "mixed-rrset-ttl",
+                    ttl_token(record),
+                    format!(
+                        "TTL {} differs from TTL {} of the other {} records of {}",
+                        ttl, first_ttl, record.rtype, record.owner
+                    ),
+                ));
+            }
+        }
+    }
+
+    lints.sort_by_key(|lint| lint.start);
+    lints
+}
+
+fn lint_soa(record: &Record) -> Vec<Lint> {
+    let [_, _, _, refresh, retry, expire, minimum] = &record.rdata[..] else {
+        return vec![];
+    };
+    let (Some(refresh_ttl), Some(retry_ttl), Some(expire_ttl), Some(minimum_ttl)) = (
+        parse_ttl(refresh.value()),
+        parse_ttl(retry.value()),
+        parse_ttl(expire.value()),
+        parse_ttl(minimum.value()),
+    ) else {
+        return vec![];
+    };
+
+    let mut lints = vec![];
+    if refresh_ttl < retry_ttl {
+        lints.push(Lint::new(
+            "soa-refresh-below-retry",
+            refresh,
+            format!(
+                "SOA refresh {} is shorter than retry {}",
+                refresh_ttl, retry_ttl
+            ),
+        ));
+    }
+    if expire_ttl < refresh_ttl {
+        lints.push(Lint::new(
+            "soa-expire-below-refresh",
+            expire,
+            format!(
+                "SOA expire {} is shorter than refresh {}",
+                expire_ttl, refresh_ttl
+            ),
+        ));
+    }
+
+    let (low, high) = NCACHE_TTL_RANGE;
+    if !(low..=high).contains(&minimum_ttl) {
+        lints.push(Lint::new(
+            "soa-ncache-ttl",
+            minimum,
+            format!(
+                "negative caching TTL {} is outside the recommended range of {} to {} seconds",
+                minimum_ttl, low, high
+            ),
+        ));
+    }
+
+    lints
+}
+
+/// The explicit TTL of a record, or its type when the TTL is inherited.
+fn ttl_token(record: &Record) -> &Token {
+    record.ttl_token.as_ref().unwrap_or(&record.type_token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lint_zone() {
+        let text = "$TTL 1h\n\
+                    @ IN SOA ns1 admin ( 1 600 1200 300 2d )\n\
+                    www 300 IN A 192.0.2.1\n\
+                    www 600 IN A 192.0.2.2\n\
+                    big 1w IN TXT \"x\"\n";
+
+        let lints = lint_zone(
+            text,
+            &LintOptions {
+                max_zone_ttl: Some(86400),
+            },
+        );
+        let codes = lints.iter().map(|l| l.code).collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                "soa-refresh-below-retry",
+                "soa-expire-below-refresh",
+                "soa-ncache-ttl",
+                "mixed-rrset-ttl",
+                "ttl-exceeds-max-zone-ttl",
+            ]
+        );
+        assert_eq!(
+            lints[0].start,
+            Pos {
+                line: 1,
+                column: 23
+            }
+        );
+        assert_eq!(lints[3].start, Pos { line: 3, column: 4 });
+
+        assert_eq!(lint_zone(text, &LintOptions::default()).len(), 4);
+    }
+}
@@ -0,0 +1,20 @@
+pub mod diff;
+pub mod export;
+pub mod lint;
+pub mod record;
+pub mod reverse;
+pub mod serial;
+pub mod stats;
+
+/// `name` with a trailing dot, so it is absolute in master files.
+pub(crate) fn fqdn(name: &str) -> String {
+    match name.ends_with('.') {
+        true => name.to_string(),
+        false => format!("{}.", name),
+    }
+}
+
+/// Whether two domain names are the same, ignoring case and a trailing dot.
+pub(crate) fn same_name(a: &str, b: &str) -> bool {
+    fqdn(a).eq_ignore_ascii_case(&fqdn(b))
+}
@@ -0,0 +1,461 @@
+//! Typed resource records.
+//!
+//! Record data is parsed from its presentation format, which is what
+//! `dns_rdata_totext` produces. Character strings (TXT, CAA values) are
+//! kept in presentation form, escapes included, so that they round-trip.
+
+use std::{
+    fmt::Display,
+    net::{Ipv4Addr, Ipv6Addr},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::syntax::zone::parse_ttl;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "RecordJson", from = "RecordJson")]
+pub struct Record {
+    pub name: String,
+    pub ttl: u32,
+    pub class: String,
+    pub data: RData,
+}
+
+/// The serialized form of a [`Record`]: `"data"` holds the typed fields of a
+/// known type, or the presentation text of anything else. Presentation text
+/// is also accepted for known types.
+#[derive(Serialize, Deserialize)]
+struct RecordJson {
+    name: String,
+    ttl: u32,
+    class: String,
+    #[serde(rename = "type")]
+    rtype: String,
+    data: serde_json::Value,
+}
+
+impl From<Record> for RecordJson {
+    fn from(record: Record) -> Self {
+        let rtype = record.rtype().to_string();
+        let data = match record.data {
+            RData::Unknown { data, .. } => serde_json::Value::String(data),
+            data => match serde_json::to_value(data) {
+                Ok(serde_json::Value::Object(mut tagged)) => {
+                    tagged.remove("data").unwrap_or(serde_json::Value::Null)
+                }
+                _ => serde_json::Value::Null,
+            },
+        };
+
+        RecordJson {
+            name: record.name,
+            ttl: record.ttl,
+            class: record.class,
+            rtype,
+            data,
+        }
+    }
+}
+
+impl From<RecordJson> for Record {
+    fn from(json: RecordJson) -> Self {
+        let rtype = json.rtype.to_ascii_uppercase();
+        let tagged = serde_json::json!({ "type": rtype, "data": json.data });
+        let data = match (serde_json::from_value(tagged), json.data) {
+            (Ok(data), _) => data,
+            (Err(_), serde_json::Value::String(text)) => RData::parse(&rtype, &text),
+            (Err(_), data) => RData::Unknown {
+                rtype,
+                data: data.to_string(),
+            },
+        };
+
+        Record {
+            name: json.name,
+            ttl: json.ttl,
+            class: json.class,
+            data,
+        }
+    }
+}
+
+impl Record {
+    pub fn rtype(&self) -> &str {
+        self.data.rtype()
+    }
+}
+
+impl Display for Record {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            self.name,
+            self.ttl,
+            self.class,
+            self.rtype(),
+            self.data
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SvcParams {
+    pub priority: u16,
+    pub target: String,
+    /// `key=value` pairs in order, with an empty value for keys that take
+    /// none such as `no-default-alpn`.
+    pub params: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum RData {
+    A(Ipv4Addr),
+    AAAA(Ipv6Addr),
+    NS(String),
+    CNAME(String),
+    PTR(String),
+    MX {
+        preference: u16,
+        exchange: String,
+    },
+    SOA {
+        mname: String,
+        rname: String,
+        serial: u32,
+        refresh: u32,
+        retry: u32,
+        expire: u32,
+        minimum: u32,
+    },
+    TXT(Vec<String>),
+    SRV {
+        priority: u16,
+        weight: u16,
+        port: u16,
+        target: String,
+    },
+    CAA {
+        flags: u8,
+        tag: String,
+        value: String,
+    },
+    SVCB(SvcParams),
+    HTTPS(SvcParams),
+    DS {
+        key_tag: u16,
+        algorithm: u8,
+        digest_type: u8,
+        digest: String,
+    },
+    DNSKEY {
+        flags: u16,
+        protocol: u8,
+        algorithm: u8,
+        public_key: String,
+    },
+    /// Any other type, or a known one that failed to parse, kept as text.
+    Unknown {
+        rtype: String,
+        data: String,
+    },
+}
+
+impl RData {
+    /// Parses record data of type `rtype` from presentation format, falling
+    /// back to [`RData::Unknown`].
+    pub fn parse(rtype: &str, text: &str) -> Self {
+        let rtype = rtype.to_ascii_uppercase();
+        Self::parse_known(&rtype, text).unwrap_or_else(|| RData::Unknown {
+            rtype,
+            data: text.trim().to_string(),
+        })
+    }
+
+    fn parse_known(rtype: &str, text: &str) -> Option<Self> {
+        let fields = split_fields(text);
+        let f = |i: usize| fields.get(i).map(String::as_str);
+        let rest = |i: usize| {
+            fields
+                .get(i..)
+                .filter(|r| !r.is_empty())
+                .map(|r| r.concat())
+        };
+
+        let data = match (rtype, fields.len()) {
+            ("A", 1) => RData::A(f(0)?.parse().ok()?),
+            ("AAAA", 1) => RData::AAAA(f(0)?.parse().ok()?),
+            ("NS", 1) => RData::NS(f(0)?.to_string()),
+            ("CNAME", 1) => RData::CNAME(f(0)?.to_string()),
+            ("PTR", 1) => RData::PTR(f(0)?.to_string()),
+            ("MX", 2) => RData::MX {
+                preference: f(0)?.parse().ok()?,
+                exchange: f(1)?.to_string(),
+            },
+            ("SOA", 7) => RData::SOA {
+                mname: f(0)?.to_string(),
+                rname: f(1)?.to_string(),
+                serial: f(2)?.parse().ok()?,
+                refresh: parse_ttl(f(3)?)?,
+                retry: parse_ttl(f(4)?)?,
+                expire: parse_ttl(f(5)?)?,
+                minimum: parse_ttl(f(6)?)?,
+            },
+            ("TXT", n) if n > 0 => {
+                RData::TXT(fields.iter().map(|s| unquote(s).to_string()).collect())
+            }
+            ("SRV", 4) => RData::SRV {
+                priority: f(0)?.parse().ok()?,
+                weight: f(1)?.parse().ok()?,
+                port: f(2)?.parse().ok()?,
+                target: f(3)?.to_string(),
+            },
+            ("CAA", 3) => RData::CAA {
+                flags: f(0)?.parse().ok()?,
+                tag: f(1)?.to_string(),
+                value: unquote(f(2)?).to_string(),
+            },
+            ("SVCB", n) if n >= 2 => RData::SVCB(SvcParams::parse(&fields)?),
+            ("HTTPS", n) if n >= 2 => RData::HTTPS(SvcParams::parse(&fields)?),
+            ("DS", n) if n >= 4 => RData::DS {
+                key_tag: f(0)?.parse().ok()?,
+                algorithm: f(1)?.parse().ok()?,
+                digest_type: f(2)?.parse().ok()?,
+                digest: rest(3)?,
+            },
+            ("DNSKEY", n) if n >= 4 => RData::DNSKEY {
+                flags: f(0)?.parse().ok()?,
+                protocol: f(1)?.parse().ok()?,
+                algorithm: f(2)?.parse().ok()?,
+                public_key: rest(3)?,
+            },
+            _ => return None,
+        };
+
+        Some(data)
+    }
+
+    pub fn rtype(&self) -> &str {
+        match self {
+            RData::A(_) => "A",
+            RData::AAAA(_) => "AAAA",
+            RData::NS(_) => "NS",
+            RData::CNAME(_) => "CNAME",
+            RData::PTR(_) => "PTR",
+            RData::MX { .. } => "MX",
+            RData::SOA { .. } => "SOA",
+            RData::TXT(_) => "TXT",
+            RData::SRV { .. } => "SRV",
+            RData::CAA { .. } => "CAA",
+            RData::SVCB(_) => "SVCB",
+            RData::HTTPS(_) => "HTTPS",
+            RData::DS { .. } => "DS",
+            RData::DNSKEY { .. } => "DNSKEY",
+            RData::Unknown { rtype, .. } => rtype,
+        }
+    }
+}
+
+impl Display for RData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RData::A(addr) => write!(f, "{}", addr),
+            RData::AAAA(addr) => write!(f, "{}", addr),
+            RData::NS(name) | RData::CNAME(name) | RData::PTR(name) => write!(f, "{}", name),
+            RData::MX {
+                preference,
+                exchange,
+            } => write!(f, "{} {}", preference, exchange),
+            RData::SOA {
+                mname,
+                rname,
+                serial,
+                refresh,
+                retry,
+                expire,
+                minimum,
+            } => write!(
+                f,
+                "{} {} {} {} {} {} {}",
+                mname, rname, serial, refresh, retry, expire, minimum
+            ),
+            RData::TXT(strings) => {
+                let strings = strings
+                    .iter()
+                    .map(|s| format!("\"{}\"", s))
+                    .collect::<Vec<_>>();
+                write!(f, "{}", strings.join(" "))
+            }
+            RData::SRV {
+                priority,
+                weight,
+                port,
+                target,
+            } => write!(f, "{} {} {} {}", priority, weight, port, target),
+            RData::CAA { flags, tag, value } => write!(f, "{} {} \"{}\"", flags, tag, value),
+            RData::SVCB(svc) | RData::HTTPS(svc) => write!(f, "{}", svc),
+            RData::DS {
+                key_tag,
+                algorithm,
+                digest_type,
+                digest,
+            } => write!(f, "{} {} {} {}", key_tag, algorithm, digest_type, digest),
+            RData::DNSKEY {
+                flags,
+                protocol,
+                algorithm,
+                public_key,
+            } => write!(f, "{} {} {} {}", flags, protocol, algorithm, public_key),
+            RData::Unknown { data, .. } => write!(f, "{}", data),
+        }
+    }
+}
+
+impl SvcParams {
+    fn parse(fields: &[String]) -> Option<Self> {
+        let params = fields[2..]
+            .iter()
+            .map(|field| match field.split_once('=') {
+                Some((key, value)) => (key.to_string(), unquote(value).to_string()),
+                None => (field.to_string(), String::new()),
+            })
+            .collect();
+
+        Some(SvcParams {
+            priority: fields[0].parse().ok()?,
+            target: fields[1].clone(),
+            params,
+        })
+    }
+}
+
+impl Display for SvcParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.priority, self.target)?;
+        for (key, value) in &self.params {
+            match value.is_empty() {
+                true => write!(f, " {}", key)?,
+                false => write!(f, " {}=\"{}\"", key, value)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Splits presentation format on whitespace, keeping quoted strings (and
+/// `key="quoted value"` pairs) together.
+fn split_fields(text: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        if escaped {
+            field.push(c);
+            escaped = false;
+            continue;
+        }
+
+        match c {
+            '\\' => {
+                field.push(c);
+                escaped = true;
+            }
+            '"' => {
+                field.push(c);
+                quoted = !quoted;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !field.is_empty() {
+                    fields.push(std::mem::take(&mut field));
+                }
+            }
+            c => field.push(c),
+        }
+    }
+    if !field.is_empty() {
+        fields.push(field);
+    }
+
+    fields
+}
+
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(rtype: &str, text: &str) -> RData {
+        let data = RData::parse(rtype, text);
+        assert_eq!(data.to_string(), text);
+        data
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            roundtrip("A", "192.0.2.1"),
+            RData::A(Ipv4Addr::new(192, 0, 2, 1))
+        );
+        assert_eq!(
+            roundtrip("MX", "10 mail.example.com."),
+            RData::MX {
+                preference: 10,
+                exchange: "mail.example.com.".to_string()
+            }
+        );
+        assert_eq!(
+            roundtrip("TXT", r#""v=spf1 -all" "say \"hi\"""#),
+            RData::TXT(vec!["v=spf1 -all".to_string(), r#"say \"hi\""#.to_string()])
+        );
+        assert_eq!(
+            roundtrip("CAA", r#"0 issue "letsencrypt.org""#),
+            RData::CAA {
+                flags: 0,
+                tag: "issue".to_string(),
+                value: "letsencrypt.org".to_string()
+            }
+        );
+        assert_eq!(
+            roundtrip("HTTPS", r#"1 . alpn="h2,h3" no-default-alpn"#),
+            RData::HTTPS(SvcParams {
+                priority: 1,
+                target: ".".to_string(),
+                params: vec![
+                    ("alpn".to_string(), "h2,h3".to_string()),
+                    ("no-default-alpn".to_string(), String::new())
+                ]
+            })
+        );
+        roundtrip(
+            "SOA",
+            "ns1.example.com. admin.example.com. 2023101001 3600 1800 604800 86400",
+        );
+
+        assert_eq!(
+            RData::parse("DNSKEY", "257 3 13 AwEAAa bcd="),
+            RData::DNSKEY {
+                flags: 257,
+                protocol: 3,
+                algorithm: 13,
+                public_key: "AwEAAabcd=".to_string()
+            }
+        );
+        assert_eq!(
+            RData::parse("A", "not-an-address"),
+            RData::Unknown {
+                rtype: "A".to_string(),
+                data: "not-an-address".to_string()
+            }
+        );
+    }
+}
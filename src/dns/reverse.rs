@@ -0,0 +1,83 @@
+//! Reverse mapping names under `in-addr.arpa.` and `ip6.arpa.`.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// The PTR owner name of an address.
+pub fn reverse_name(addr: IpAddr) -> String {
+    match addr {
+        IpAddr::V4(addr) => {
+            let [a, b, c, d] = addr.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa.", d, c, b, a)
+        }
+        IpAddr::V6(addr) => {
+            let mut name = String::new();
+            for byte in addr.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0xf, byte >> 4));
+            }
+            name.push_str("ip6.arpa.");
+            name
+        }
+    }
+}
+
+/// The address a full-length reverse name stands for.
+pub fn reverse_address(name: &str) -> Option<IpAddr> {
+    let name = name.trim_end_matches('.').to_ascii_lowercase();
+
+    if let Some(labels) = name.strip_suffix(".in-addr.arpa") {
+        let octets = labels
+            .split('.')
+            .rev()
+            .map(|l| l.parse::<u8>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let [a, b, c, d] = octets[..] else {
+            return None;
+        };
+        return Some(IpAddr::V4(Ipv4Addr::new(a, b, c, d)));
+    }
+
+    if let Some(labels) = name.strip_suffix(".ip6.arpa") {
+        let nibbles = labels
+            .split('.')
+            .rev()
+            .map(|l| match l.len() {
+                1 => u8::from_str_radix(l, 16).ok(),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        if nibbles.len() != 32 {
+            return None;
+        }
+
+        let mut octets = [0u8; 16];
+        for (i, pair) in nibbles.chunks(2).enumerate() {
+            octets[i] = pair[0] << 4 | pair[1];
+        }
+        return Some(IpAddr::V6(Ipv6Addr::from(octets)));
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse() {
+        let v4: IpAddr = "192.168.1.10".parse().unwrap();
+        assert_eq!(reverse_name(v4), "10.1.168.192.in-addr.arpa.");
+        assert_eq!(reverse_address("10.1.168.192.IN-ADDR.ARPA."), Some(v4));
+
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        let name = reverse_name(v6);
+        assert_eq!(
+            name,
+            "1.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.0.8.b.d.0.1.0.0.2.ip6.arpa."
+        );
+        assert_eq!(reverse_address(&name), Some(v6));
+
+        assert_eq!(reverse_address("1.168.192.in-addr.arpa."), None);
+        assert_eq!(reverse_address("www.example.com."), None);
+    }
+}
@@ -0,0 +1,179 @@
+//! SOA serial numbering schemes.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::syntax::{
+    byte_offset,
+    zone::{self, Token},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SerialScheme {
+    /// A plain counter incremented on every change.
+    Counter,
+    /// `YYYYMMDDnn`, a date followed by a two digit revision.
+    Date,
+    /// Seconds since the Unix epoch.
+    UnixTime,
+}
+
+impl SerialScheme {
+    pub fn detect(serial: u32, now: u64) -> Self {
+        if date_of(serial).is_some() {
+            SerialScheme::Date
+        } else if (1_000_000_000..=now + 86400).contains(&u64::from(serial)) {
+            SerialScheme::UnixTime
+        } else {
+            SerialScheme::Counter
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SerialScheme::Counter => "counter",
+            SerialScheme::Date => "YYYYMMDDnn date",
+            SerialScheme::UnixTime => "unix time",
+        }
+    }
+
+    /// The serial following `serial` in this scheme at time `now`.
+    pub fn next(&self, serial: u32, now: u64) -> u32 {
+        match self {
+            SerialScheme::Counter => serial.wrapping_add(1),
+            SerialScheme::Date => {
+                let (year, month, day) = civil_date(now / 86400);
+                let today =
+                    u64::from(year) * 1_000_000 + u64::from(month) * 10_000 + u64::from(day) * 100;
+                match u32::try_from(today) {
+                    Ok(today) if today > serial => today,
+                    _ => serial.wrapping_add(1),
+                }
+            }
+            SerialScheme::UnixTime => match u32::try_from(now) {
+                Ok(now) if now > serial => now,
+                _ => serial.wrapping_add(1),
+            },
+        }
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// The serial following `serial`, keeping its scheme.
+pub fn next_serial(serial: u32, now: u64) -> u32 {
+    SerialScheme::detect(serial, now).next(serial, now)
+}
+
+/// The serial field of the first SOA record of a zone file.
+pub fn find_serial(text: &str) -> Option<(u32, Token)> {
+    let zone = zone::parse(text, None);
+    let soa = zone.records.into_iter().find(|r| r.rtype == "SOA")?;
+    let token = soa.rdata.into_iter().nth(2)?;
+
+    Some((token.text.parse().ok()?, token))
+}
+
+/// Returns `text` with its SOA serial incremented, along with the new
+/// serial.
+pub fn bump_serial(text: &str, now: u64) -> Option<(String, u32)> {
+    let (serial, token) = find_serial(text)?;
+    let next = next_serial(serial, now);
+
+    let start = byte_offset(text, token.start)?;
+    let end = byte_offset(text, token.end)?;
+
+    let mut bumped = text.to_string();
+    bumped.replace_range(start..end, &next.to_string());
+
+    Some((bumped, next))
+}
+
+fn date_of(serial: u32) -> Option<(u32, u32, u32)> {
+    let date = serial / 100;
+    let (year, month, day) = (date / 10_000, date / 100 % 100, date % 100);
+
+    let valid = (1990..=2100).contains(&year)
+        && (1..=12).contains(&month)
+        && (1..=days_in_month(year, month)).contains(&day);
+    valid.then_some((year, month, day))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Converts days since the Unix epoch to a proleptic Gregorian date.
+fn civil_date(days: u64) -> (u32, u32, u32) {
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year as u32, month as u32, day as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2024-03-15T12:00:00Z
+    const NOW: u64 = 1_710_504_000;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(SerialScheme::detect(42, NOW), SerialScheme::Counter);
+        assert_eq!(SerialScheme::detect(2024031001, NOW), SerialScheme::Date);
+        assert_eq!(
+            SerialScheme::detect(1_700_000_000, NOW),
+            SerialScheme::UnixTime
+        );
+        assert_eq!(SerialScheme::detect(2024131001, NOW), SerialScheme::Counter);
+        assert_eq!(SerialScheme::detect(2024022901, NOW), SerialScheme::Date);
+        assert_eq!(SerialScheme::detect(2023022901, NOW), SerialScheme::Counter);
+        assert_eq!(SerialScheme::detect(2024023101, NOW), SerialScheme::Counter);
+        assert_eq!(SerialScheme::detect(2024043101, NOW), SerialScheme::Counter);
+        assert_eq!(SerialScheme::detect(2000022901, NOW), SerialScheme::Date);
+        assert_eq!(SerialScheme::detect(2100022901, NOW), SerialScheme::Counter);
+    }
+
+    #[test]
+    fn test_next_serial() {
+        assert_eq!(civil_date(NOW / 86400), (2024, 3, 15));
+
+        assert_eq!(next_serial(42, NOW), 43);
+        assert_eq!(next_serial(u32::MAX, NOW), 0);
+        assert_eq!(next_serial(2024031001, NOW), 2024031500);
+        assert_eq!(next_serial(2024031500, NOW), 2024031501);
+        assert_eq!(next_serial(1_700_000_000, NOW), NOW as u32);
+        assert_eq!(next_serial(NOW as u32, NOW), NOW as u32 + 1);
+    }
+
+    #[test]
+    fn test_bump_serial() {
+        let text = "@ IN SOA ns1 admin (\n    2024031001 ; serial\n    3600 1800 604800 86400 )\n";
+
+        let (bumped, serial) = bump_serial(text, NOW).unwrap();
+        assert_eq!(serial, 2024031500);
+        assert_eq!(
+            bumped,
+            "@ IN SOA ns1 admin (\n    2024031500 ; serial\n    3600 1800 604800 86400 )\n"
+        );
+    }
+}
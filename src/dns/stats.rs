@@ -0,0 +1,204 @@
+//! Statistics about the contents of a loaded zone.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+
+use super::{
+    fqdn,
+    record::{RData, Record},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SoaTimers {
+    pub serial: u32,
+    pub refresh: u32,
+    pub retry: u32,
+    pub expire: u32,
+    pub minimum: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Dnssec {
+    pub dnskey: bool,
+    pub rrsig: bool,
+    pub nsec: bool,
+    pub nsec3: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ZoneStats {
+    pub origin: String,
+    pub records: usize,
+    pub names: usize,
+    pub types: BTreeMap<String, usize>,
+    /// NS RRsets below the apex.
+    pub delegations: usize,
+    /// Address records at or below a delegation.
+    pub glue: usize,
+    pub wildcards: usize,
+    pub dnssec: Dnssec,
+    pub min_ttl: Option<u32>,
+    pub max_ttl: Option<u32>,
+    pub soa: Option<SoaTimers>,
+}
+
+impl ZoneStats {
+    pub fn new(origin: &str, records: &[Record]) -> Self {
+        let origin = fqdn(origin);
+        let apex = origin.to_ascii_lowercase();
+
+        let mut names = BTreeSet::new();
+        let mut types: BTreeMap<String, usize> = BTreeMap::new();
+        let mut cuts = BTreeSet::new();
+        let mut soa = None;
+        for record in records {
+            let name = record.name.to_ascii_lowercase();
+            *types.entry(record.rtype().to_string()).or_default() += 1;
+
+            match &record.data {
+                RData::NS(_) if name != apex => {
+                    cuts.insert(name.clone());
+                }
+                RData::SOA {
+                    serial,
+                    refresh,
+                    retry,
+                    expire,
+                    minimum,
+                    ..
+                } => {
+                    soa = Some(SoaTimers {
+                        serial: *serial,
+                        refresh: *refresh,
+                        retry: *retry,
+                        expire: *expire,
+                        minimum: *minimum,
+                    });
+                }
+                _ => {}
+            }
+            names.insert(name);
+        }
+
+        let below_cut = |name: &str| {
+            cuts.iter()
+                .any(|cut| name == cut || name.ends_with(&format!(".{}", cut)))
+        };
+        let glue = records
+            .iter()
+            .filter(|r| matches!(r.data, RData::A(_) | RData::AAAA(_)))
+            .filter(|r| below_cut(&r.name.to_ascii_lowercase()))
+            .count();
+        let has = |rtype: &str| types.contains_key(rtype);
+
+        ZoneStats {
+            origin,
+            records: records.len(),
+            wildcards: names.iter().filter(|n| n.starts_with("*.")).count(),
+            names: names.len(),
+            delegations: cuts.len(),
+            glue,
+            dnssec: Dnssec {
+                dnskey: has("DNSKEY"),
+                rrsig: has("RRSIG"),
+                nsec: has("NSEC"),
+                nsec3: has("NSEC3"),
+            },
+            types,
+            min_ttl: records.iter().map(|r| r.ttl).min(),
+            max_ttl: records.iter().map(|r| r.ttl).max(),
+            soa,
+        }
+    }
+
+    /// A plain text report with one value per line and a table of record
+    /// counts by type.
+    pub fn to_table(&self) -> String {
+        let optional = |value: Option<u32>| value.map_or("-".to_string(), |v| v.to_string());
+        let yes_no = |value: bool| if value { "yes" } else { "no" };
+
+        let mut rows = vec![
+            ("zone", self.origin.clone()),
+            ("records", self.records.to_string()),
+            ("names", self.names.to_string()),
+            ("delegations", self.delegations.to_string()),
+            ("glue", self.glue.to_string()),
+            ("wildcards", self.wildcards.to_string()),
+            ("dnskey", yes_no(self.dnssec.dnskey).to_string()),
+            ("rrsig", yes_no(self.dnssec.rrsig).to_string()),
+            ("nsec", yes_no(self.dnssec.nsec).to_string()),
+            ("nsec3", yes_no(self.dnssec.nsec3).to_string()),
+            ("min ttl", optional(self.min_ttl)),
+            ("max ttl", optional(self.max_ttl)),
+        ];
+        let soa = self.soa.as_ref();
+        rows.extend([
+            ("serial", optional(soa.map(|s| s.serial))),
+            ("refresh", optional(soa.map(|s| s.refresh))),
+            ("retry", optional(soa.map(|s| s.retry))),
+            ("expire", optional(soa.map(|s| s.expire))),
+            ("minimum", optional(soa.map(|s| s.minimum))),
+        ]);
+
+        let mut text = String::new();
+        for (name, value) in rows {
+            text.push_str(&format!("{:<12} {}\n", name, value));
+        }
+        text.push_str(&format!("\n{:<12} {}\n", "type", "count"));
+        for (rtype, count) in &self.types {
+            text.push_str(&format!("{:<12} {}\n", rtype, count));
+        }
+
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let records = [
+            (
+                "example.com.",
+                3600,
+                "SOA",
+                "ns1.example.com. admin.example.com. 7 3600 900 604800 300",
+            ),
+            ("example.com.", 3600, "NS", "ns1.example.com."),
+            ("ns1.example.com.", 300, "A", "192.0.2.1"),
+            ("*.example.com.", 300, "A", "192.0.2.2"),
+            ("sub.example.com.", 86400, "NS", "ns.sub.example.com."),
+            ("ns.sub.example.com.", 86400, "A", "192.0.2.3"),
+            ("ns.sub.example.com.", 86400, "AAAA", "2001:db8::3"),
+            ("example.com.", 3600, "DNSKEY", "257 3 13 AAAA"),
+        ]
+        .map(|(name, ttl, rtype, data)| Record {
+            name: name.to_string(),
+            ttl,
+            class: "IN".to_string(),
+            data: RData::parse(rtype, data),
+        });
+
+        let stats = ZoneStats::new("example.com", &records);
+        assert_eq!(stats.origin, "example.com.");
+        assert_eq!(stats.records, 8);
+        assert_eq!(stats.names, 5);
+        assert_eq!(stats.types["A"], 3);
+        assert_eq!(stats.delegations, 1);
+        assert_eq!(stats.glue, 2);
+        assert_eq!(stats.wildcards, 1);
+        assert_eq!(
+            stats.dnssec,
+            Dnssec {
+                dnskey: true,
+                ..Default::default()
+            }
+        );
+        assert_eq!((stats.min_ttl, stats.max_ttl), (Some(300), Some(86400)));
+        assert_eq!(stats.soa.as_ref().map(|s| s.serial), Some(7));
+        assert!(stats.to_table().contains("glue         2\n"));
+    }
+}
@@ -5,4 +5,13 @@
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
-pub mod bind;
\ No newline at end of file
+pub mod acl;
+pub mod bind;
+pub mod check;
+pub mod config;
+pub mod diagnostic;
+pub mod dns;
+pub mod lsp;
+pub mod path;
+pub mod report;
+pub mod syntax;
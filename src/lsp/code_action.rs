@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use lsp_types::{CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, Uri, WorkspaceEdit};
+
+use crate::{
+    bind::grammar::clause_names,
+    diagnostic::{Edit, Fix},
+    syntax::{
+        conf::{self, ConfText, Statement, Token, TokenKind},
+        Pos,
+    },
+};
+
+/// Options that were renamed, as `(old, new)`.
+const RENAMES: [(&str, &str); 3] = [
+    ("masters", "primaries"),
+    ("default-masters", "default-primaries"),
+    ("max-ixfr-log-size", "max-journal-size"),
+];
+
+type Fixer = fn(&Context, &str) -> Vec<Fix>;
+
+/// Known `named.conf` parser and checker messages, as the text around the
+/// part that is captured and the fixer called with it.
+const PATTERNS: [(&str, &str, Fixer); 6] = [
+    ("unknown option '", "'", unknown_option),
+    ("option '", "' is deprecated", obsolete_option),
+    ("option '", "' is obsolete", obsolete_option),
+    ("option '", "' no longer exists", obsolete_option),
+    ("token '", "' is deprecated", deprecated_token),
+    ("missing ';' before ", "", missing_semicolon),
+];
+
+struct Context<'a> {
+    conf: &'a ConfText,
+    line: u32,
+}
+
+/// Computes quick fixes for the diagnostics the client sent along with a
+/// `textDocument/codeAction` request.
+pub fn code_actions(uri: &Uri, text: &str, diagnostics: &[Diagnostic]) -> Vec<CodeActionOrCommand> {
+    let conf = conf::parse(text);
+
+    let mut actions = vec![];
+    for diagnostic in diagnostics {
+        let line = diagnostic.range.start.line;
+        for fix in conf_fixes(&conf, line, &diagnostic.message) {
+            let edits = fix.edits.iter().map(Edit::to_lsp).collect();
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title: fix.title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(uri.clone(), edits)])),
+                    ..Default::default()
+                }),
+                is_preferred: Some(fix.preferred),
+                ..Default::default()
+            }));
+        }
+    }
+
+    actions
+}
+
+/// The fixes of a known parser or checker message reported on `line`.
+pub fn conf_fixes(conf: &ConfText, line: u32, message: &str) -> Vec<Fix> {
+    let context = Context { conf, line };
+    let message = message.trim();
+
+    let mut fixes = vec![];
+    for (prefix, suffix, fixer) in PATTERNS {
+        if let Some(capture) = capture(message, prefix, suffix) {
+            fixes.extend(fixer(&context, capture));
+        }
+    }
+
+    fixes
+}
+
+fn capture<'a>(message: &'a str, prefix: &str, suffix: &str) -> Option<&'a str> {
+    let rest = &message[message.find(prefix)? + prefix.len()..];
+    if suffix.is_empty() {
+        return Some(rest);
+    }
+
+    Some(&rest[..rest.find(suffix)?])
+}
+
+fn unknown_option(context: &Context, name: &str) -> Vec<Fix> {
+    let Some(token) = find_word(context, name) else {
+        return vec![];
+    };
+
+    if let Some((_, new)) = RENAMES.iter().find(|(old, _)| *old == name) {
+        return vec![replace(token, new, true)];
+    }
+
+    let max = (name.len() / 4).clamp(1, 3);
+    let mut candidates = clause_names()
+        .iter()
+        .map(|known| (edit_distance(name, known), known))
+        .filter(|(distance, _)| *distance <= max)
+        .collect::<Vec<_>>();
+    candidates.sort();
+
+    candidates
+        .iter()
+        .take(3)
+        .enumerate()
+        .map(|(i, (_, known))| replace(token, known, i == 0))
+        .collect()
+}
+
+fn obsolete_option(context: &Context, name: &str) -> Vec<Fix> {
+    if let Some((_, new)) = RENAMES.iter().find(|(old, _)| *old == name) {
+        if let Some(token) = find_word(context, name) {
+            return vec![replace(token, new, true)];
+        }
+    }
+
+    let Some(statement) = find_statement(&context.conf.statements, name, context.line) else {
+        return vec![];
+    };
+
+    vec![Fix {
+        title: format!("Remove '{}'", name),
+        edits: vec![Edit::new(statement_range(context.conf, statement), "")],
+        preferred: true,
+    }]
+}
+
+/// Removes a deprecated keyword together with its value, e.g. the
+/// `port 53` of `query-source address * port 53;`.
+fn deprecated_token(context: &Context, name: &str) -> Vec<Fix> {
+    let code = code_tokens(context.conf);
+    let Some(i) = code
+        .iter()
+        .position(|t| t.start.line == context.line && t.text == name)
+    else {
+        return vec![];
+    };
+
+    let (Some(before), Some(value)) = (i.checked_sub(1).map(|i| code[i]), code.get(i + 1)) else {
+        return vec![];
+    };
+    if value.kind != TokenKind::Word {
+        return vec![];
+    }
+
+    vec![Fix {
+        title: format!("Remove '{} {}'", name, value.text),
+        edits: vec![Edit::new((before.end, value.end), "")],
+        preferred: true,
+    }]
+}
+
+fn missing_semicolon(context: &Context, before: &str) -> Vec<Fix> {
+    let code = code_tokens(context.conf);
+
+    let next = match before.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        Some(name) => code
+            .iter()
+            .position(|t| t.start.line == context.line && (t.text == name || t.value() == name))
+            .or_else(|| code.iter().position(|t| t.start.line == context.line)),
+        // "before end of file"
+        None => Some(code.len()),
+    };
+    let Some(previous) = next.and_then(|i| i.checked_sub(1)).map(|i| code[i]) else {
+        return vec![];
+    };
+
+    vec![Fix {
+        title: "Insert missing ';'".to_string(),
+        edits: vec![Edit::new((previous.end, previous.end), ";")],
+        preferred: true,
+    }]
+}
+
+fn replace(token: &Token, new: &str, preferred: bool) -> Fix {
+    Fix {
+        title: format!("Replace '{}' with '{}'", token.text, new),
+        edits: vec![Edit::new((token.start, token.end), new)],
+        preferred,
+    }
+}
+
+fn code_tokens(conf: &ConfText) -> Vec<&Token> {
+    conf.tokens
+        .iter()
+        .filter(|t| t.kind != TokenKind::Comment)
+        .collect()
+}
+
+fn find_word<'a>(context: &Context<'a>, name: &str) -> Option<&'a Token> {
+    context
+        .conf
+        .tokens
+        .iter()
+        .find(|t| t.kind == TokenKind::Word && t.start.line == context.line && t.text == name)
+}
+
+fn find_statement<'a>(statements: &'a [Statement], name: &str, line: u32) -> Option<&'a Statement> {
+    statements.iter().find_map(|statement| {
+        if statement.name() == Some(name) && statement.start.line == line {
+            return Some(statement);
+        }
+
+        let block = statement.block.as_ref()?;
+        find_statement(&block.statements, name, line)
+    })
+}
+
+/// The range of a statement, widened to whole lines when nothing else
+/// shares them so that no blank line is left behind.
+fn statement_range(conf: &ConfText, statement: &Statement) -> (Pos, Pos) {
+    let shares_line = conf.tokens.iter().any(|t| {
+        (t.start.line == statement.start.line && t.end <= statement.start)
+            || (t.end.line == statement.end.line && t.start >= statement.end)
+    });
+
+    if shares_line {
+        (statement.start, statement.end)
+    } else {
+        (
+            Pos {
+                line: statement.start.line,
+                column: 0,
+            },
+            Pos {
+                line: statement.end.line + 1,
+                column: 0,
+            },
+        )
+    }
+}
+
+/// Optimal string alignment distance, counting a transposition of two
+/// adjacent characters as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    let mut d = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use lsp_types::{Position, Range, TextEdit};
+
+    use super::*;
+
+    fn edits(text: &str, line: u32, message: &str) -> Vec<(String, Vec<TextEdit>)> {
+        let uri = Uri::from_str("file:///named.conf").unwrap();
+        let diagnostic = Diagnostic {
+            range: Range {
+                start: Position { line, character: 0 },
+                end: Position { line, character: 0 },
+            },
+            message: message.to_string(),
+            ..Default::default()
+        };
+
+        code_actions(&uri, text, &[diagnostic])
+            .into_iter()
+            .map(|action| match action {
+                CodeActionOrCommand::CodeAction(action) => {
+                    let edits = action.edit.and_then(|e| e.changes?.into_values().next());
+                    (action.title, edits.unwrap())
+                }
+                CodeActionOrCommand::Command(_) => unreachable!(),
+            })
+            .collect()
+    }
+
+    fn edit(line: u32, start: u32, end_line: u32, end: u32, new_text: &str) -> TextEdit {
+        TextEdit {
+            range: Range {
+                start: Position {
+                    line,
+                    character: start,
+                },
+                end: Position {
+                    line: end_line,
+                    character: end,
+                },
+            },
+            new_text: new_text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_rename() {
+        let text = "zone \"a\" {\n    masters { 192.0.2.1; };\n};\n";
+
+        let actions = edits(text, 1, " option 'masters' is deprecated");
+        assert_eq!(
+            actions,
+            vec![(
+                "Replace 'masters' with 'primaries'".to_string(),
+                vec![edit(1, 4, 1, 11, "primaries")]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let text = "options {\n    dialup yes;\n};\n";
+
+        let actions = edits(
+            text,
+            1,
+            " option 'dialup' is obsolete and should be removed",
+        );
+        assert_eq!(
+            actions,
+            vec![("Remove 'dialup'".to_string(), vec![edit(1, 0, 2, 0, "")])]
+        );
+
+        // DLV has no replacement, dnssec-validation means something else
+        let text = "options {\n    dnssec-lookaside . trust-anchor dlv.isc.org;\n};\n";
+        let actions = edits(text, 1, " option 'dnssec-lookaside' is obsolete");
+        assert_eq!(
+            actions,
+            vec![(
+                "Remove 'dnssec-lookaside'".to_string(),
+                vec![edit(1, 0, 2, 0, "")]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_deprecated_token() {
+        let text = "options {\n    query-source address * port 53;\n};\n";
+
+        let actions = edits(text, 1, " token 'port' is deprecated");
+        assert_eq!(
+            actions,
+            vec![("Remove 'port 53'".to_string(), vec![edit(1, 26, 1, 34, "")])]
+        );
+    }
+
+    #[test]
+    fn test_missing_semicolon() {
+        let text = "options {\n    recursion yes\n};\nzone \"a\" { type hint; }\n";
+
+        let actions = edits(text, 2, " missing ';' before '}'");
+        assert_eq!(
+            actions,
+            vec![(
+                "Insert missing ';'".to_string(),
+                vec![edit(1, 17, 1, 17, ";")]
+            )]
+        );
+
+        let actions = edits(text, 3, " missing ';' before end of file");
+        assert_eq!(
+            actions,
+            vec![(
+                "Insert missing ';'".to_string(),
+                vec![edit(3, 23, 3, 23, ";")]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("recursion", "recursion"), 0);
+        assert_eq!(edit_distance("recursoin", "recursion"), 1);
+        assert_eq!(edit_distance("allow-recurson", "allow-recursion"), 1);
+        assert_eq!(edit_distance("abc", ""), 3);
+    }
+}
@@ -0,0 +1,104 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    net::IpAddr,
+    path::PathBuf,
+};
+
+use crate::{
+    check::{
+        consistency::{check_consistency, LoadedZone, Mismatch},
+        zones::ZoneCheck,
+    },
+    diagnostic::{Diagnostic, Severity},
+    dns::record::RData,
+    syntax::{
+        zone::{self, absolute},
+        Pos,
+    },
+};
+
+/// Reports forward/reverse mismatches between the primary zones loaded by
+/// [`check_all_zones`](crate::check::zones::check_all_zones) on the
+/// offending records, grouped by zone file.
+pub fn consistency_diagnostics(checks: &[ZoneCheck]) -> HashMap<PathBuf, Vec<Diagnostic>> {
+    let mut seen = HashSet::new();
+    let mut loaded = vec![];
+    for check in checks
+        .iter()
+        .filter(|c| c.zone.is_primary() && c.serial.is_some())
+    {
+        let Some(file) = check
+            .zone
+            .file
+            .as_ref()
+            .filter(|f| seen.insert(f.to_path_buf()))
+        else {
+            continue;
+        };
+
+        loaded.push(LoadedZone {
+            origin: check.zone.name.clone(),
+            file: file.clone(),
+            records: check.records.clone(),
+        });
+    }
+
+    let mut diagnostics: HashMap<PathBuf, Vec<Diagnostic>> = HashMap::new();
+    let mut texts = HashMap::new();
+    for mismatch in check_consistency(&loaded) {
+        let text = texts
+            .entry(mismatch.file.clone())
+            .or_insert_with(|| fs::read_to_string(&mismatch.file).unwrap_or_default());
+
+        let (start, end) = locate(text, &mismatch);
+        diagnostics
+            .entry(mismatch.file.clone())
+            .or_default()
+            .push(Diagnostic::new(
+                &mismatch.file,
+                start,
+                end,
+                Severity::Warning,
+                "forward-reverse-mismatch",
+                mismatch.message,
+            ));
+    }
+
+    diagnostics
+}
+
+/// The range of the record a mismatch is about, or the start of the file
+/// when it cannot be found.
+fn locate(text: &str, mismatch: &Mismatch) -> (Pos, Pos) {
+    let origin = absolute(&mismatch.origin, Some("."));
+    let parsed = zone::parse(text, Some(&origin));
+    let record = &mismatch.record;
+
+    let candidates = parsed
+        .records
+        .iter()
+        .filter(|r| r.owner.eq_ignore_ascii_case(&record.name) && r.rtype == record.rtype())
+        .collect::<Vec<_>>();
+    let matches = |r: &zone::Record| {
+        let Some(value) = r.rdata.first().map(|t| t.value()) else {
+            return false;
+        };
+        match &record.data {
+            RData::A(addr) => value.parse::<IpAddr>().ok() == Some(IpAddr::V4(*addr)),
+            RData::AAAA(addr) => value.parse::<IpAddr>().ok() == Some(IpAddr::V6(*addr)),
+            RData::PTR(target) => absolute(value, Some(&origin)).eq_ignore_ascii_case(target),
+            _ => false,
+        }
+    };
+
+    let found = candidates
+        .iter()
+        .find(|r| matches(r))
+        .or(candidates.first());
+
+    match found {
+        Some(r) => (r.start, r.end),
+        None => (Pos::default(), Pos::default()),
+    }
+}
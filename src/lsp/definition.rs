@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use lsp_types::{Location, Position, Range};
+
+use crate::path::PathResolver;
+use crate::syntax::{
+    conf::{self, Statement, Token, TokenKind},
+    Pos,
+};
+
+use super::path_to_uri;
+
+/// Clauses whose argument is a file that can be opened.
+const FILE_CLAUSES: &[&str] = &["include", "file"];
+
+/// The file named by the `include` or `file` path under `position`, as
+/// `named` would find it through `resolver`.
+pub fn path_definition(
+    text: &str,
+    position: Position,
+    resolver: &PathResolver,
+) -> Option<Location> {
+    let pos = Pos {
+        line: position.line,
+        column: position.character,
+    };
+    let conf = conf::parse(text);
+    let token = find_path(&conf.statements, pos)?;
+
+    let path = resolver.resolve(token.value());
+    file_location(&path)
+}
+
+fn find_path(statements: &[Statement], pos: Pos) -> Option<&Token> {
+    for statement in statements {
+        if !(statement.start..=statement.end).contains(&pos) {
+            continue;
+        }
+
+        let is_file = statement
+            .name()
+            .is_some_and(|name| FILE_CLAUSES.contains(&name));
+        let path = statement
+            .words
+            .get(1)
+            .filter(|w| is_file && w.kind == TokenKind::Quoted && (w.start..=w.end).contains(&pos));
+        if path.is_some() {
+            return path;
+        }
+
+        if let Some(block) = &statement.block {
+            return find_path(&block.statements, pos);
+        }
+    }
+
+    None
+}
+
+fn file_location(path: &Path) -> Option<Location> {
+    if !path.is_file() {
+        return None;
+    }
+
+    Some(Location {
+        uri: path_to_uri(path)?,
+        range: Range::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+
+    #[test]
+    fn test_path_definition() {
+        let dir = std::env::temp_dir().join(format!("bls-definition-{}", std::process::id()));
+        fs::create_dir_all(dir.join("etc/bind")).unwrap();
+        fs::write(dir.join("etc/bind/db.example"), "").unwrap();
+
+        let text = "options { directory \"/etc/bind\"; };\n\
+                    zone \"example\" { type primary; file \"db.example\"; };\n\
+                    include \"/etc/bind/missing.conf\";\n";
+        let resolver = PathResolver::new(&dir.join("named.conf"))
+            .with_mappings(vec![(PathBuf::from("/etc/bind"), dir.join("etc/bind"))])
+            .with_text(text);
+
+        let location = path_definition(text, Position::new(1, 42), &resolver).unwrap();
+        assert!(location.uri.as_str().ends_with("/etc/bind/db.example"));
+        assert_eq!(path_definition(text, Position::new(1, 3), &resolver), None);
+        assert_eq!(path_definition(text, Position::new(2, 12), &resolver), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
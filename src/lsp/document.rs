@@ -0,0 +1,93 @@
+use std::collections::HashMap;
+
+use lsp_types::Uri;
+
+use super::uri_to_path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    NamedConf,
+    Zone,
+}
+
+impl DocumentKind {
+    /// Guesses whether a document is a zone file or a configuration file,
+    /// first from the client's language id, then from the file name and
+    /// finally from the content.
+    pub fn detect(uri: &Uri, language_id: Option<&str>, text: &str) -> Self {
+        match language_id {
+            Some("zone" | "dns" | "bind-zone" | "zonefile") => return DocumentKind::Zone,
+            Some("named" | "bind" | "named.conf" | "bind-config") => {
+                return DocumentKind::NamedConf
+            }
+            _ => {}
+        }
+
+        let path = uri_to_path(uri);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if name.starts_with("db.")
+            || [".zone", ".db", ".rev", ".hosts"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+        {
+            return DocumentKind::Zone;
+        }
+        if name.contains("named") || name.ends_with(".conf") {
+            return DocumentKind::NamedConf;
+        }
+
+        let zone_like = text.lines().any(|line| {
+            let line = line.trim_start().to_ascii_uppercase();
+            line.starts_with("$ORIGIN") || line.starts_with("$TTL") || line.contains(" SOA ")
+        });
+        if zone_like {
+            DocumentKind::Zone
+        } else {
+            DocumentKind::NamedConf
+        }
+    }
+}
+
+pub struct Document {
+    pub text: String,
+    pub kind: DocumentKind,
+    pub version: i32,
+}
+
+/// The documents currently open in the client.
+#[derive(Default)]
+pub struct Documents {
+    docs: HashMap<Uri, Document>,
+}
+
+impl Documents {
+    pub fn open(&mut self, uri: Uri, language_id: &str, text: String, version: i32) {
+        let kind = DocumentKind::detect(&uri, Some(language_id), &text);
+        self.docs.insert(
+            uri,
+            Document {
+                text,
+                kind,
+                version,
+            },
+        );
+    }
+
+    pub fn change(&mut self, uri: &Uri, text: String, version: i32) {
+        if let Some(doc) = self.docs.get_mut(uri) {
+            doc.text = text;
+            doc.version = version;
+        }
+    }
+
+    pub fn close(&mut self, uri: &Uri) {
+        self.docs.remove(uri);
+    }
+
+    pub fn get(&self, uri: &Uri) -> Option<&Document> {
+        self.docs.get(uri)
+    }
+}
@@ -0,0 +1,243 @@
+use std::{collections::HashMap, fs};
+
+use lsp_types::{DocumentSymbol, Range, SymbolKind};
+
+use crate::path::PathResolver;
+use crate::syntax::zone::{self, Record};
+
+use super::range;
+
+const MAX_INCLUDE_DEPTH: usize = 8;
+
+/// Builds the outline of a zone file: one symbol per owner name with its
+/// RRsets as children. Records pulled in by `$INCLUDE` are listed under
+/// the directive that includes them, with the included files found
+/// through `resolver` the way `named` opens them.
+pub fn zone_symbols(
+    text: &str,
+    origin: Option<&str>,
+    resolver: &PathResolver,
+) -> Vec<DocumentSymbol> {
+    zone_symbols_at(text, origin, resolver, None, 0)
+}
+
+fn zone_symbols_at(
+    text: &str,
+    origin: Option<&str>,
+    resolver: &PathResolver,
+    at: Option<Range>,
+    depth: usize,
+) -> Vec<DocumentSymbol> {
+    let zone = zone::parse(text, origin);
+    let mut symbols = owner_symbols(&zone.records, at);
+
+    if depth >= MAX_INCLUDE_DEPTH {
+        return symbols;
+    }
+
+    for include in &zone.includes {
+        let directive = at.unwrap_or(range(include.start, include.end));
+
+        let file = resolver.resolve(&include.path);
+        let children = match fs::read_to_string(&file) {
+            Ok(text) => zone_symbols_at(
+                &text,
+                include.origin.as_deref(),
+                resolver,
+                Some(directive),
+                depth + 1,
+            ),
+            Err(_) => vec![],
+        };
+
+        #[allow(deprecated)]
+        symbols.push(DocumentSymbol {
+            name: format!("$INCLUDE {}", include.path),
+            detail: include.origin.clone(),
+            kind: SymbolKind::FILE,
+            tags: None,
+            deprecated: None,
+            range: directive,
+            selection_range: directive,
+            children: Some(children),
+        });
+    }
+
+    symbols.sort_by_key(|s| (s.range.start.line, s.range.start.character));
+    symbols
+}
+
+/// Groups records by owner and then by type, in order of first appearance.
+/// When `at` is given every symbol is placed there instead of at the
+/// record's own position.
+fn owner_symbols(records: &[Record], at: Option<Range>) -> Vec<DocumentSymbol> {
+    let mut owners: Vec<Vec<Vec<&Record>>> = vec![];
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for record in records {
+        let i = *index
+            .entry(record.owner.to_ascii_lowercase())
+            .or_insert_with(|| {
+                owners.push(vec![]);
+                owners.len() - 1
+            });
+
+        let rrsets = &mut owners[i];
+        match rrsets
+            .iter_mut()
+            .find(|rrset| rrset[0].rtype == record.rtype)
+        {
+            Some(rrset) => rrset.push(record),
+            None => rrsets.push(vec![record]),
+        }
+    }
+
+    owners
+        .into_iter()
+        .map(|rrsets| {
+            let children = rrsets
+                .iter()
+                .map(|rrset| rrset_symbol(rrset, at))
+                .collect::<Vec<_>>();
+
+            let first = rrsets[0][0];
+            let selection_range = at.unwrap_or_else(|| {
+                rrsets
+                    .iter()
+                    .flatten()
+                    .find_map(|r| r.owner_token.as_ref())
+                    .map(|t| range(t.start, t.end))
+                    .unwrap_or(range(first.start, first.start))
+            });
+
+            #[allow(deprecated)]
+            DocumentSymbol {
+                name: first.owner.clone(),
+                detail: Some(
+                    rrsets
+                        .iter()
+                        .map(|rrset| rrset[0].rtype.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" "),
+                ),
+                kind: SymbolKind::NAMESPACE,
+                tags: None,
+                deprecated: None,
+                range: at.unwrap_or_else(|| enclosing(&children)),
+                selection_range,
+                children: Some(children),
+            }
+        })
+        .collect()
+}
+
+fn rrset_symbol(rrset: &[&Record], at: Option<Range>) -> DocumentSymbol {
+    let first = rrset[0];
+    let detail = match rrset {
+        [record] => {
+            let rdata = record
+                .rdata
+                .iter()
+                .map(|t| t.text.as_str())
+                .collect::<Vec<_>>()
+                .join(" ");
+            if rdata.chars().count() > 60 {
+                format!("{}...", rdata.chars().take(60).collect::<String>())
+            } else {
+                rdata
+            }
+        }
+        _ => format!("{} records", rrset.len()),
+    };
+
+    let ranges = rrset
+        .iter()
+        .map(|r| range(r.start, r.end))
+        .collect::<Vec<_>>();
+
+    #[allow(deprecated)]
+    DocumentSymbol {
+        name: first.rtype.clone(),
+        detail: Some(detail),
+        kind: SymbolKind::FIELD,
+        tags: None,
+        deprecated: None,
+        range: at.unwrap_or_else(|| union(&ranges)),
+        selection_range: at.unwrap_or_else(|| range(first.type_token.start, first.type_token.end)),
+        children: None,
+    }
+}
+
+fn enclosing(symbols: &[DocumentSymbol]) -> Range {
+    union(&symbols.iter().map(|s| s.range).collect::<Vec<_>>())
+}
+
+fn union(ranges: &[Range]) -> Range {
+    let start = ranges.iter().map(|r| r.start).min().unwrap_or_default();
+    let end = ranges.iter().map(|r| r.end).max().unwrap_or_default();
+    Range { start, end }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn test_zone_symbols() {
+        let text = r#"$ORIGIN example.com.
+$TTL 1h
+@   IN  SOA ns1 admin ( 1 3600 1800 604800 86400 )
+    IN  NS  ns1
+    IN  NS  ns2
+ns1 IN  A 192.168.1.1
+ns2 IN  A 192.168.1.2
+@   IN  MX 10 mail
+"#;
+
+        let resolver = PathResolver::new(Path::new("named.conf"));
+        let symbols = zone_symbols(text, None, &resolver);
+        let outline = symbols
+            .iter()
+            .map(|s| {
+                let children = s.children.as_ref().unwrap();
+                (
+                    s.name.as_str(),
+                    children.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            outline,
+            vec![
+                ("example.com.", vec!["SOA", "NS", "MX"]),
+                ("ns1.example.com.", vec!["A"]),
+                ("ns2.example.com.", vec!["A"]),
+            ]
+        );
+        assert_eq!(symbols[0].range.start.line, 2);
+        assert_eq!(symbols[0].range.end.line, 7);
+    }
+
+    #[test]
+    fn test_include_symbols() {
+        let dir = std::env::temp_dir().join(format!("bls-test-symbols-{}", std::process::id()));
+        fs::create_dir_all(dir.join("zones")).unwrap();
+        fs::write(dir.join("zones/hosts"), "www IN A 192.0.2.1\n").unwrap();
+
+        // relative to the working directory of named, not to the zone file
+        let text = "$INCLUDE zones/hosts example.com.\n";
+        let resolver = PathResolver::new(&dir.join("named.conf"));
+        let symbols = zone_symbols(text, None, &resolver);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].name, "$INCLUDE zones/hosts");
+        assert_eq!(symbols[0].detail.as_deref(), Some("example.com."));
+        let children = symbols[0].children.as_ref().unwrap();
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].name, "www.example.com.");
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use lsp_types::{FoldingRange, FoldingRangeKind};
+
+use crate::syntax::{
+    conf::{self, Statement},
+    zone, Pos,
+};
+
+use super::document::DocumentKind;
+
+pub fn folding_ranges(kind: DocumentKind, text: &str) -> Vec<FoldingRange> {
+    let mut ranges = match kind {
+        DocumentKind::NamedConf => conf_ranges(text),
+        DocumentKind::Zone => zone_ranges(text),
+    };
+
+    ranges.sort_by_key(|r| (r.start_line, r.end_line));
+    ranges
+}
+
+fn conf_ranges(text: &str) -> Vec<FoldingRange> {
+    let conf = conf::parse(text);
+
+    let mut ranges = vec![];
+    blocks(&conf.statements, &mut ranges);
+
+    let code_lines = conf
+        .tokens
+        .iter()
+        .filter(|t| t.kind != conf::TokenKind::Comment)
+        .flat_map(|t| [t.start.line, t.end.line])
+        .collect::<HashSet<_>>();
+    let comments = conf
+        .tokens
+        .iter()
+        .filter(|t| t.kind == conf::TokenKind::Comment)
+        .map(|t| (t.start, t.end))
+        .collect::<Vec<_>>();
+    ranges.extend(comment_ranges(&comments, &code_lines));
+
+    ranges
+}
+
+/// Folds every `{ ... }` spanning several lines, keeping the line with
+/// the closing brace visible.
+fn blocks(statements: &[Statement], ranges: &mut Vec<FoldingRange>) {
+    for statement in statements {
+        let Some(block) = &statement.block else {
+            continue;
+        };
+
+        if let Some(close) = &block.close {
+            if close.start.line > block.open.start.line + 1 {
+                ranges.push(region(block.open.start.line, close.start.line - 1, None));
+            }
+        }
+
+        blocks(&block.statements, ranges);
+    }
+}
+
+fn zone_ranges(text: &str) -> Vec<FoldingRange> {
+    let zone = zone::parse(text, None);
+
+    let mut ranges = zone
+        .records
+        .iter()
+        .filter(|r| r.end.line > r.start.line)
+        .map(|r| region(r.start.line, r.end.line, None))
+        .collect::<Vec<_>>();
+
+    let code_lines = zone
+        .tokens
+        .iter()
+        .filter(|t| t.kind != zone::TokenKind::Comment)
+        .flat_map(|t| [t.start.line, t.end.line])
+        .collect::<HashSet<_>>();
+    let comments = zone
+        .tokens
+        .iter()
+        .filter(|t| t.kind == zone::TokenKind::Comment)
+        .map(|t| (t.start, t.end))
+        .collect::<Vec<_>>();
+    ranges.extend(comment_ranges(&comments, &code_lines));
+
+    ranges
+}
+
+/// Folds block comments spanning several lines and runs of consecutive
+/// full-line comments. Comments trailing code are left alone.
+fn comment_ranges(comments: &[(Pos, Pos)], code_lines: &HashSet<u32>) -> Vec<FoldingRange> {
+    let mut ranges = vec![];
+    let mut run: Option<(u32, u32)> = None;
+
+    for &(start, end) in comments {
+        if code_lines.contains(&start.line) || code_lines.contains(&end.line) {
+            continue;
+        }
+
+        run = match run {
+            Some((first, last)) if start.line == last + 1 => Some((first, end.line)),
+            Some((first, last)) => {
+                if last > first {
+                    ranges.push(region(first, last, Some(FoldingRangeKind::Comment)));
+                }
+                Some((start.line, end.line))
+            }
+            None => Some((start.line, end.line)),
+        };
+    }
+
+    if let Some((first, last)) = run {
+        if last > first {
+            ranges.push(region(first, last, Some(FoldingRangeKind::Comment)));
+        }
+    }
+
+    ranges
+}
+
+fn region(start_line: u32, end_line: u32, kind: Option<FoldingRangeKind>) -> FoldingRange {
+    FoldingRange {
+        start_line,
+        start_character: None,
+        end_line,
+        end_character: None,
+        kind,
+        collapsed_text: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(ranges: &[FoldingRange]) -> Vec<(u32, u32, bool)> {
+        ranges
+            .iter()
+            .map(|r| {
+                let comment = r.kind == Some(FoldingRangeKind::Comment);
+                (r.start_line, r.end_line, comment)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_conf_ranges() {
+        let text = r#"// first
+// second
+options {
+    directory "/var/named";
+    /* a
+       b */
+    listen-on { 127.0.0.1; };
+};
+zone "example.com" { type primary; };
+"#;
+
+        let ranges = folding_ranges(DocumentKind::NamedConf, text);
+        assert_eq!(
+            lines(&ranges),
+            vec![(0, 1, true), (2, 6, false), (4, 5, true)]
+        );
+    }
+
+    #[test]
+    fn test_zone_ranges() {
+        let text = r#"; header
+; more
+@ IN SOA ns1 admin (
+        1 ; serial
+        3600 1800 604800 86400 )
+www IN A 192.0.2.1 ; web
+"#;
+
+        let ranges = folding_ranges(DocumentKind::Zone, text);
+        assert_eq!(lines(&ranges), vec![(0, 1, true), (2, 4, false)]);
+    }
+}
@@ -0,0 +1,123 @@
+use lsp_types::{FormattingOptions, Position, Range, TextEdit};
+
+use crate::config::FormatOptions;
+use crate::syntax::conf::{self, TokenKind};
+
+/// Re-indents a configuration file by block depth, honouring the project's
+/// format options over the editor's. Returns a single edit replacing the
+/// document, or none when it is already formatted.
+pub fn conf_formatting(
+    text: &str,
+    options: &FormattingOptions,
+    project: &FormatOptions,
+) -> Vec<TextEdit> {
+    let use_tabs = project.use_tabs.unwrap_or(!options.insert_spaces);
+    let indent = if use_tabs {
+        "\t".to_string()
+    } else {
+        " ".repeat(project.indent_width.unwrap_or(options.tab_size) as usize)
+    };
+
+    let formatted = format_conf(text, &indent);
+    if formatted == text {
+        return vec![];
+    }
+
+    let last = text.rsplit('\n').next().unwrap_or_default();
+    let end = Position {
+        line: text.matches('\n').count() as u32,
+        character: last.encode_utf16().count() as u32,
+    };
+
+    vec![TextEdit {
+        range: Range {
+            start: Position::default(),
+            end,
+        },
+        new_text: formatted,
+    }]
+}
+
+/// Indents every line with `indent` once per enclosing block and strips
+/// trailing whitespace. Lines inside multi-line comments and strings are
+/// kept as they are.
+pub fn format_conf(text: &str, indent: &str) -> String {
+    let lines = text.split('\n').collect::<Vec<_>>();
+    let mut depths = vec![None; lines.len()];
+    let mut verbatim = vec![false; lines.len()];
+
+    let mut depth: usize = 0;
+    for token in conf::lex(text) {
+        let line = token.start.line as usize;
+        if depths[line].is_none() && !verbatim[line] {
+            depths[line] = Some(match token.kind {
+                TokenKind::CloseBrace => depth.saturating_sub(1),
+                _ => depth,
+            });
+        }
+        for line in token.start.line + 1..=token.end.line {
+            verbatim[line as usize] = true;
+        }
+
+        match token.kind {
+            TokenKind::OpenBrace => depth += 1,
+            TokenKind::CloseBrace => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let cr = if line.ends_with('\r') { "\r" } else { "" };
+            match (verbatim[i], depths[i]) {
+                (true, _) => line.to_string(),
+                (false, Some(depth)) => format!("{}{}{}", indent.repeat(depth), line.trim(), cr),
+                (false, None) => cr.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_conf() {
+        let text = "options {\n\
+                    directory \"/var/named\";   \n\
+                    \n\
+                          listen-on { any; };\n\
+                    /* a\n   comment */\n\
+                    };\n\
+                    zone \"example\" {\n\
+                    type primary; };\n";
+
+        assert_eq!(
+            format_conf(text, "\t"),
+            "options {\n\
+             \tdirectory \"/var/named\";\n\
+             \n\
+             \tlisten-on { any; };\n\
+             \t/* a\n   comment */\n\
+             };\n\
+             zone \"example\" {\n\
+             \ttype primary; };\n"
+        );
+
+        let options = FormattingOptions {
+            tab_size: 2,
+            insert_spaces: true,
+            ..Default::default()
+        };
+        let edits = conf_formatting(text, &options, &FormatOptions::default());
+        assert_eq!(edits[0].range.end, Position::new(9, 0));
+        assert!(edits[0].new_text.contains("\n  directory"));
+
+        let formatted = format_conf(text, "  ");
+        assert!(conf_formatting(&formatted, &options, &FormatOptions::default()).is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+pub mod code_action;
+pub mod consistency;
+pub mod definition;
+pub mod document;
+pub mod document_symbol;
+pub mod folding_range;
+pub mod formatting;
+pub mod semantic_tokens;
+pub mod serial;
+pub mod workspace_symbol;
+
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use lsp_types::{Position, Range, Uri};
+
+use crate::syntax::Pos;
+
+pub fn uri_to_path(uri: &Uri) -> PathBuf {
+    let path = uri.as_str();
+    PathBuf::from(decode_path(path.strip_prefix("file://").unwrap_or(path)))
+}
+
+pub fn path_to_uri(path: &Path) -> Option<Uri> {
+    Uri::from_str(&file_uri(path)).ok()
+}
+
+/// The `file` URI of an absolute path.
+pub fn file_uri(path: &Path) -> String {
+    format!("file://{}", encode_path(path))
+}
+
+/// A path as a URI path, with every byte but unreserved characters and `/`
+/// percent-encoded.
+pub fn encode_path(path: &Path) -> String {
+    let mut encoded = String::new();
+    for &byte in path.as_os_str().as_encoded_bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    encoded
+}
+
+/// Decodes the percent-encoded bytes of a URI path. Malformed escapes are
+/// kept as they are.
+fn decode_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escape = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escape {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+pub fn position(pos: Pos) -> Position {
+    Position {
+        line: pos.line,
+        character: pos.column,
+    }
+}
+
+pub fn range(start: Pos, end: Pos) -> Range {
+    Range {
+        start: position(start),
+        end: position(end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_uri() {
+        let path = Path::new("/srv/dns zones/#1/100%.conf");
+        let uri = path_to_uri(path).unwrap();
+        assert_eq!(uri.as_str(), "file:///srv/dns%20zones/%231/100%25.conf");
+        assert_eq!(uri_to_path(&uri), path);
+    }
+}
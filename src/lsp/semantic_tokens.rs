@@ -0,0 +1,540 @@
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr},
+};
+
+use lsp_types::{
+    Range, SemanticToken, SemanticTokenModifier, SemanticTokenType, SemanticTokens,
+    SemanticTokensLegend,
+};
+
+use crate::syntax::{
+    conf::{self, Statement},
+    zone::{self, parse_ttl, Record},
+    Pos,
+};
+
+use super::{document::DocumentKind, position};
+
+// indices into the legend returned by `legend`
+const KEYWORD: u32 = 0;
+const PROPERTY: u32 = 1;
+const VARIABLE: u32 = 2;
+const NAMESPACE: u32 = 3;
+const NUMBER: u32 = 4;
+const STRING: u32 = 5;
+const COMMENT: u32 = 6;
+const OPERATOR: u32 = 7;
+const TYPE: u32 = 8;
+const ENUM_MEMBER: u32 = 9;
+const MACRO: u32 = 10;
+const PARAMETER: u32 = 11;
+
+const DECLARATION: u32 = 1 << 0;
+const ADDRESS: u32 = 1 << 1;
+const PREFIX: u32 = 1 << 2;
+const DURATION: u32 = 1 << 3;
+
+pub fn legend() -> SemanticTokensLegend {
+    SemanticTokensLegend {
+        token_types: vec![
+            SemanticTokenType::KEYWORD,
+            SemanticTokenType::PROPERTY,
+            SemanticTokenType::VARIABLE,
+            SemanticTokenType::NAMESPACE,
+            SemanticTokenType::NUMBER,
+            SemanticTokenType::STRING,
+            SemanticTokenType::COMMENT,
+            SemanticTokenType::OPERATOR,
+            SemanticTokenType::TYPE,
+            SemanticTokenType::ENUM_MEMBER,
+            SemanticTokenType::MACRO,
+            SemanticTokenType::PARAMETER,
+        ],
+        token_modifiers: vec![
+            SemanticTokenModifier::DECLARATION,
+            SemanticTokenModifier::new("address"),
+            SemanticTokenModifier::new("prefix"),
+            SemanticTokenModifier::new("duration"),
+        ],
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Highlight {
+    start: Pos,
+    end: Pos,
+    ty: u32,
+    modifiers: u32,
+}
+
+impl Highlight {
+    fn new(start: Pos, end: Pos, ty: u32, modifiers: u32) -> Self {
+        Highlight {
+            start,
+            end,
+            ty,
+            modifiers,
+        }
+    }
+}
+
+/// Computes the semantic tokens of a document, restricted to `range` if
+/// given.
+pub fn semantic_tokens(kind: DocumentKind, text: &str, range: Option<Range>) -> SemanticTokens {
+    let highlights = match kind {
+        DocumentKind::NamedConf => conf_highlights(text),
+        DocumentKind::Zone => zone_highlights(text),
+    };
+
+    SemanticTokens {
+        result_id: None,
+        data: encode(text, highlights, range),
+    }
+}
+
+// statements whose first argument declares a name
+const DECLARING: [(&str, u32); 9] = [
+    ("zone", NAMESPACE),
+    ("view", NAMESPACE),
+    ("acl", VARIABLE),
+    ("key", VARIABLE),
+    ("dnssec-policy", VARIABLE),
+    ("tls", VARIABLE),
+    ("primaries", VARIABLE),
+    ("masters", VARIABLE),
+    ("parental-agents", VARIABLE),
+];
+
+const LITERALS: [&str; 8] = [
+    "yes",
+    "no",
+    "true",
+    "false",
+    "any",
+    "none",
+    "localhost",
+    "localnets",
+];
+
+fn conf_highlights(text: &str) -> Vec<Highlight> {
+    let conf = conf::parse(text);
+
+    let mut highlights = conf
+        .tokens
+        .iter()
+        .filter(|t| t.kind == conf::TokenKind::Comment)
+        .map(|t| Highlight::new(t.start, t.end, COMMENT, 0))
+        .collect::<Vec<_>>();
+
+    let mut names = HashSet::new();
+    declared_names(&conf.statements, &mut names);
+    conf_statements(&conf.statements, 0, &names, &mut highlights);
+
+    highlights
+}
+
+fn declared_names(statements: &[Statement], names: &mut HashSet<String>) {
+    for statement in statements {
+        if let (Some("acl" | "key"), Some(name)) = (statement.name(), statement.words.get(1)) {
+            names.insert(name.value().to_string());
+        }
+        if let Some(block) = &statement.block {
+            declared_names(&block.statements, names);
+        }
+    }
+}
+
+fn conf_statements(
+    statements: &[Statement],
+    depth: usize,
+    names: &HashSet<String>,
+    highlights: &mut Vec<Highlight>,
+) {
+    for statement in statements {
+        let mut words = statement.words.iter().peekable();
+
+        // list elements such as `10.0.0.0/8;`, `trusted;` or `key "x";`
+        // have no clause name
+        let clause = statement.name().filter(|name| {
+            value(name, names).is_none() && (*name != "key" || statement.block.is_some())
+        });
+        if let Some(clause) = clause {
+            let first = words.next().expect("a named statement has words");
+            let ty = if depth == 0 { KEYWORD } else { PROPERTY };
+            highlights.push(Highlight::new(first.start, first.end, ty, 0));
+
+            let declared = DECLARING
+                .iter()
+                .find(|(keyword, _)| *keyword == clause)
+                .filter(|_| statement.block.is_some());
+            if let (Some((_, ty)), Some(name)) = (declared, words.peek()) {
+                highlights.push(Highlight::new(name.start, name.end, *ty, DECLARATION));
+                words.next();
+            }
+        }
+
+        let mut after_key = false;
+        for word in words {
+            let highlight = match word.kind {
+                conf::TokenKind::Bang => Some((OPERATOR, 0)),
+                conf::TokenKind::Quoted if after_key => Some((VARIABLE, 0)),
+                conf::TokenKind::Quoted => Some((STRING, 0)),
+                _ if after_key => Some((VARIABLE, 0)),
+                _ if word.text == "key" => Some((KEYWORD, 0)),
+                _ => value(&word.text, names),
+            };
+            after_key = word.text == "key";
+
+            if let Some((ty, modifiers)) = highlight {
+                highlights.push(Highlight::new(word.start, word.end, ty, modifiers));
+            }
+        }
+
+        if let Some(block) = &statement.block {
+            conf_statements(&block.statements, depth + 1, names, highlights);
+        }
+    }
+}
+
+/// Classifies a value by its shape: addresses, prefixes, numbers,
+/// durations, well-known literals and references to declared names.
+fn value(word: &str, names: &HashSet<String>) -> Option<(u32, u32)> {
+    if word.parse::<IpAddr>().is_ok() {
+        Some((NUMBER, ADDRESS))
+    } else if is_prefix(word) {
+        Some((NUMBER, PREFIX))
+    } else if word.chars().all(|c| c.is_ascii_digit()) {
+        Some((NUMBER, 0))
+    } else if is_duration(word) {
+        Some((NUMBER, DURATION))
+    } else if LITERALS.contains(&word) {
+        Some((ENUM_MEMBER, 0))
+    } else if names.contains(word) {
+        Some((VARIABLE, 0))
+    } else {
+        None
+    }
+}
+
+fn is_prefix(word: &str) -> bool {
+    let Some((addr, len)) = word.split_once('/') else {
+        return false;
+    };
+    let Ok(len) = len.parse::<u8>() else {
+        return false;
+    };
+
+    match addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => len <= 32,
+        Ok(IpAddr::V6(_)) => len <= 128,
+        // BIND accepts shortened IPv4 prefixes such as `10/8`
+        Err(_) => {
+            len <= 32
+                && addr.split('.').count() < 4
+                && format!("{}{}", addr, ".0".repeat(4 - addr.split('.').count()))
+                    .parse::<Ipv4Addr>()
+                    .is_ok()
+        }
+    }
+}
+
+/// Matches BIND's TTL-style (`1w2d`) and ISO 8601 (`P1DT12H`) durations.
+fn is_duration(word: &str) -> bool {
+    if !word.starts_with(|c: char| c.is_ascii_digit() || c == 'P' || c == 'p') {
+        return false;
+    }
+    if parse_ttl(word).is_some() {
+        return true;
+    }
+
+    let Some(rest) = word.strip_prefix(['P', 'p']) else {
+        return false;
+    };
+    let (date, time) = match rest.split_once(['T', 't']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (rest, None),
+    };
+
+    let units = |part: &str, allowed: &str| {
+        let mut digits = false;
+        for c in part.chars() {
+            if c.is_ascii_digit() {
+                digits = true;
+            } else if digits && allowed.contains(c.to_ascii_uppercase()) {
+                digits = false;
+            } else {
+                return false;
+            }
+        }
+        !digits
+    };
+
+    units(date, "YMWD")
+        && time.is_none_or(|t| !t.is_empty() && units(t, "HMS"))
+        && !(date.is_empty() && time.is_none())
+}
+
+// rdata fields holding domain names, by record type
+fn name_fields(rtype: &str) -> &'static [usize] {
+    match rtype {
+        "NS" | "CNAME" | "DNAME" | "PTR" => &[0],
+        "SOA" | "RP" => &[0, 1],
+        "MX" | "AFSDB" | "KX" | "SVCB" | "HTTPS" => &[1],
+        "SRV" => &[3],
+        "NAPTR" => &[5],
+        _ => &[],
+    }
+}
+
+fn zone_highlights(text: &str) -> Vec<Highlight> {
+    let zone = zone::parse(text, None);
+
+    let mut highlights = zone
+        .tokens
+        .iter()
+        .filter(|t| t.kind == zone::TokenKind::Comment)
+        .map(|t| Highlight::new(t.start, t.end, COMMENT, 0))
+        .collect::<Vec<_>>();
+
+    for directive in &zone.directives {
+        let name = &directive.name;
+        highlights.push(Highlight::new(name.start, name.end, MACRO, 0));
+
+        let args: &[u32] = match name.text.to_ascii_uppercase().as_str() {
+            "$ORIGIN" => &[NAMESPACE],
+            "$TTL" => &[NUMBER],
+            "$INCLUDE" => &[STRING, NAMESPACE],
+            _ => &[],
+        };
+        for (arg, &ty) in directive.args.iter().zip(args) {
+            let modifiers = match ty {
+                NUMBER if arg.value().parse::<u32>().is_err() => DURATION,
+                _ => 0,
+            };
+            highlights.push(Highlight::new(arg.start, arg.end, ty, modifiers));
+        }
+    }
+
+    for record in &zone.records {
+        record_highlights(record, &mut highlights);
+    }
+
+    highlights
+}
+
+fn record_highlights(record: &Record, highlights: &mut Vec<Highlight>) {
+    if let Some(owner) = &record.owner_token {
+        highlights.push(Highlight::new(
+            owner.start,
+            owner.end,
+            NAMESPACE,
+            DECLARATION,
+        ));
+    }
+    if let Some(ttl) = &record.ttl_token {
+        let modifiers = match ttl.text.parse::<u32>() {
+            Ok(_) => 0,
+            Err(_) => DURATION,
+        };
+        highlights.push(Highlight::new(ttl.start, ttl.end, NUMBER, modifiers));
+    }
+    if let Some(class) = &record.class_token {
+        highlights.push(Highlight::new(class.start, class.end, KEYWORD, 0));
+    }
+
+    let rtype = &record.type_token;
+    highlights.push(Highlight::new(rtype.start, rtype.end, TYPE, 0));
+
+    let names = name_fields(&record.rtype);
+    for (i, field) in record.rdata.iter().enumerate() {
+        let (ty, modifiers) = if field.kind == zone::TokenKind::Quoted {
+            (STRING, 0)
+        } else if names.contains(&i) {
+            (NAMESPACE, 0)
+        } else if field.text.parse::<IpAddr>().is_ok() {
+            (NUMBER, ADDRESS)
+        } else if field.text.chars().all(|c| c.is_ascii_digit()) {
+            (NUMBER, 0)
+        } else if record.rtype == "SOA" && parse_ttl(&field.text).is_some() {
+            (NUMBER, DURATION)
+        } else {
+            (PARAMETER, 0)
+        };
+        highlights.push(Highlight::new(field.start, field.end, ty, modifiers));
+    }
+}
+
+/// Delta-encodes highlights as LSP expects, splitting the ones spanning
+/// several lines since not every client supports multiline tokens.
+fn encode(text: &str, mut highlights: Vec<Highlight>, range: Option<Range>) -> Vec<SemanticToken> {
+    let lines = text
+        .split('\n')
+        .map(|l| l.trim_end_matches('\r').encode_utf16().count() as u32)
+        .collect::<Vec<_>>();
+
+    highlights.sort_by_key(|h| h.start);
+
+    let mut segments = vec![];
+    for h in highlights {
+        for line in h.start.line..=h.end.line {
+            let start = if line == h.start.line {
+                h.start.column
+            } else {
+                0
+            };
+            let end = if line == h.end.line {
+                h.end.column
+            } else {
+                lines.get(line as usize).copied().unwrap_or(0)
+            };
+            if end > start {
+                segments.push((line, start, end - start, h.ty, h.modifiers));
+            }
+        }
+    }
+
+    let visible = |line: u32, start: u32, len: u32| {
+        let Some(range) = range else {
+            return true;
+        };
+        let from = position(Pos {
+            line,
+            column: start,
+        });
+        let to = position(Pos {
+            line,
+            column: start + len,
+        });
+        to > range.start && from < range.end
+    };
+
+    let mut data = vec![];
+    let mut prev = (0, 0);
+    for (line, start, length, token_type, modifiers) in segments {
+        if !visible(line, start, length) {
+            continue;
+        }
+
+        data.push(SemanticToken {
+            delta_line: line - prev.0,
+            delta_start: if line == prev.0 {
+                start - prev.1
+            } else {
+                start
+            },
+            length,
+            token_type,
+            token_modifiers_bitset: modifiers,
+        });
+        prev = (line, start);
+    }
+
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(tokens: &[SemanticToken]) -> Vec<(u32, u32, u32, u32, u32)> {
+        let mut line = 0;
+        let mut start = 0;
+        tokens
+            .iter()
+            .map(|t| {
+                if t.delta_line > 0 {
+                    start = 0;
+                }
+                line += t.delta_line;
+                start += t.delta_start;
+                (
+                    line,
+                    start,
+                    t.length,
+                    t.token_type,
+                    t.token_modifiers_bitset,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_conf_tokens() {
+        let text = r#"acl trusted { 10/8; };
+options {
+    allow-query { !trusted; key "xfer"; };
+    max-cache-ttl 1w2d; // comment
+};
+"#;
+
+        let tokens = semantic_tokens(DocumentKind::NamedConf, text, None);
+        assert_eq!(
+            decode(&tokens.data),
+            vec![
+                (0, 0, 3, KEYWORD, 0),
+                (0, 4, 7, VARIABLE, DECLARATION),
+                (0, 14, 4, NUMBER, PREFIX),
+                (1, 0, 7, KEYWORD, 0),
+                (2, 4, 11, PROPERTY, 0),
+                (2, 18, 1, OPERATOR, 0),
+                (2, 19, 7, VARIABLE, 0),
+                (2, 28, 3, KEYWORD, 0),
+                (2, 32, 6, VARIABLE, 0),
+                (3, 4, 13, PROPERTY, 0),
+                (3, 18, 4, NUMBER, DURATION),
+                (3, 24, 10, COMMENT, 0),
+            ]
+        );
+
+        let text = "key \"xfer\" { algorithm hmac-sha256; };\n";
+        let tokens = semantic_tokens(DocumentKind::NamedConf, text, None);
+        assert_eq!(
+            decode(&tokens.data),
+            vec![
+                (0, 0, 3, KEYWORD, 0),
+                (0, 4, 6, VARIABLE, DECLARATION),
+                (0, 13, 9, PROPERTY, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_zone_tokens() {
+        let text = "$TTL 1h\nwww 300 IN A 192.0.2.1 ; web\n";
+
+        let tokens = semantic_tokens(DocumentKind::Zone, text, None);
+        assert_eq!(
+            decode(&tokens.data),
+            vec![
+                (0, 0, 4, MACRO, 0),
+                (0, 5, 2, NUMBER, DURATION),
+                (1, 0, 3, NAMESPACE, DECLARATION),
+                (1, 4, 3, NUMBER, 0),
+                (1, 8, 2, KEYWORD, 0),
+                (1, 11, 1, TYPE, 0),
+                (1, 13, 9, NUMBER, ADDRESS),
+                (1, 23, 5, COMMENT, 0),
+            ]
+        );
+
+        let range = Range {
+            start: position(Pos { line: 1, column: 0 }),
+            end: position(Pos { line: 1, column: 7 }),
+        };
+        let tokens = semantic_tokens(DocumentKind::Zone, text, Some(range));
+        assert_eq!(tokens.data.len(), 2);
+    }
+
+    #[test]
+    fn test_is_duration() {
+        assert!(is_duration("1w2d"));
+        assert!(is_duration("P1D"));
+        assert!(is_duration("PT12H"));
+        assert!(is_duration("P1DT12H30M"));
+        assert!(!is_duration("P"));
+        assert!(!is_duration("PT"));
+        assert!(!is_duration("primary"));
+        assert!(!is_duration("1h30"));
+    }
+}
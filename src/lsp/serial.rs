@@ -0,0 +1,231 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, Diagnostic, NumberOrString, Uri, WorkspaceEdit,
+};
+
+use crate::{
+    diagnostic::{self, Edit, Fix, Severity},
+    dns::serial::{find_serial, now, SerialScheme},
+    syntax::zone,
+};
+
+const SERIAL_UNCHANGED: &str = "serial-unchanged";
+
+/// The versions of files committed at git `HEAD`, kept until `HEAD` moves
+/// so that `git` is not run on every save.
+#[derive(Debug, Default)]
+pub struct HeadVersions {
+    /// By path, the commit `HEAD` pointed to and the file's text there.
+    versions: HashMap<PathBuf, (String, Option<String>)>,
+}
+
+impl HeadVersions {
+    /// The text of `path` at `HEAD`, if it is committed there.
+    pub fn get(&mut self, path: &Path) -> Option<String> {
+        let commit = head_commit(path.parent()?)?;
+        match self.versions.get(path) {
+            Some((cached, text)) if *cached == commit => text.clone(),
+            _ => {
+                let text = head_version(path, &commit);
+                self.versions
+                    .insert(path.to_path_buf(), (commit, text.clone()));
+                text
+            }
+        }
+    }
+}
+
+/// Warns when the records of a zone file differ from the version committed
+/// at git `HEAD` while its SOA serial does not.
+pub fn serial_diagnostics(
+    heads: &mut HeadVersions,
+    path: &Path,
+    text: &str,
+) -> Vec<diagnostic::Diagnostic> {
+    let Some(head) = heads.get(path) else {
+        return vec![];
+    };
+    let (Some((serial, token)), Some((head_serial, _))) = (find_serial(text), find_serial(&head))
+    else {
+        return vec![];
+    };
+
+    if serial != head_serial || records(text) == records(&head) {
+        return vec![];
+    }
+
+    let mut diagnostic = diagnostic::Diagnostic::new(
+        path,
+        token.start,
+        token.end,
+        Severity::Warning,
+        SERIAL_UNCHANGED,
+        format!(
+            "zone content changed since HEAD but the SOA serial is still {}",
+            serial
+        ),
+    );
+    diagnostic.fixes.push(increment_serial(serial, &token));
+
+    vec![diagnostic]
+}
+
+/// Offers to increment the SOA serial of a zone file, as a quick fix for
+/// the unchanged serial warning or as a plain action otherwise.
+pub fn serial_actions(
+    uri: &Uri,
+    text: &str,
+    diagnostics: &[Diagnostic],
+) -> Vec<CodeActionOrCommand> {
+    let Some((serial, token)) = find_serial(text) else {
+        return vec![];
+    };
+
+    let fix = increment_serial(serial, &token);
+    let fixes = diagnostics
+        .iter()
+        .filter(|d| d.code == Some(NumberOrString::String(SERIAL_UNCHANGED.to_string())))
+        .cloned()
+        .collect::<Vec<_>>();
+    let (kind, diagnostics) = if fixes.is_empty() {
+        (CodeActionKind::REFACTOR_REWRITE, None)
+    } else {
+        (CodeActionKind::QUICKFIX, Some(fixes))
+    };
+
+    let edits = fix.edits.iter().map(Edit::to_lsp).collect();
+
+    vec![CodeActionOrCommand::CodeAction(CodeAction {
+        title: fix.title,
+        kind: Some(kind),
+        is_preferred: Some(diagnostics.is_some()),
+        diagnostics,
+        edit: Some(WorkspaceEdit {
+            changes: Some(HashMap::from([(uri.clone(), edits)])),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })]
+}
+
+/// Replaces the serial with the next one of the scheme it follows.
+fn increment_serial(serial: u32, token: &zone::Token) -> Fix {
+    let now = now();
+    let scheme = SerialScheme::detect(serial, now);
+    let next = scheme.next(serial, now);
+
+    Fix {
+        title: format!("Increment SOA serial to {} ({})", next, scheme.name()),
+        edits: vec![Edit::new((token.start, token.end), &next.to_string())],
+        preferred: true,
+    }
+}
+
+/// The records of a zone file, ignoring formatting, comments and the SOA
+/// serial.
+fn records(text: &str) -> Vec<(String, Option<u32>, String, Vec<String>)> {
+    zone::parse(text, None)
+        .records
+        .into_iter()
+        .map(|r| {
+            let mut rdata = r.rdata.iter().map(|t| t.text.clone()).collect::<Vec<_>>();
+            if r.rtype == "SOA" && rdata.len() > 2 {
+                rdata.remove(2);
+            }
+            (r.owner.to_ascii_lowercase(), r.ttl, r.rtype, rdata)
+        })
+        .collect()
+}
+
+fn head_version(path: &Path, commit: &str) -> Option<String> {
+    let dir = path.parent()?;
+    let name = path.file_name()?.to_str()?;
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("show")
+        .arg(format!("{}:./{}", commit, name))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    String::from_utf8(output.stdout).ok()
+}
+
+/// The commit `HEAD` points to in the repository containing `dir`, read
+/// from the git directory rather than by running `git`.
+fn head_commit(dir: &Path) -> Option<String> {
+    let git_dir = dir.ancestors().find_map(git_dir)?;
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let Some(name) = head.trim().strip_prefix("ref: ") else {
+        return Some(head.trim().to_string());
+    };
+
+    // branches of linked worktrees live in the main git directory
+    let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(common) => git_dir.join(common.trim()),
+        Err(_) => git_dir,
+    };
+    if let Ok(commit) = fs::read_to_string(common_dir.join(name)) {
+        return Some(commit.trim().to_string());
+    }
+    let packed = fs::read_to_string(common_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        let (commit, packed_name) = line.split_once(' ')?;
+        (packed_name == name).then(|| commit.to_string())
+    })
+}
+
+/// The git directory of a work tree at `dir`: its `.git` directory, or the
+/// one a `.git` file points to.
+fn git_dir(dir: &Path) -> Option<PathBuf> {
+    let dot_git = dir.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
+    }
+
+    let link = fs::read_to_string(&dot_git).ok()?;
+    let target = link.trim().strip_prefix("gitdir: ")?;
+    Some(dir.join(target))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_head_commit() {
+        let dir = std::env::temp_dir().join(format!("bls-test-head-{}", std::process::id()));
+        let git_dir = dir.join(".git");
+        let zones = dir.join("zones");
+        fs::create_dir_all(git_dir.join("refs/heads")).unwrap();
+        fs::create_dir_all(&zones).unwrap();
+
+        fs::write(git_dir.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        assert_eq!(head_commit(&zones), None);
+
+        fs::write(
+            git_dir.join("packed-refs"),
+            "# pack-refs\naaaa refs/heads/main\n",
+        )
+        .unwrap();
+        assert_eq!(head_commit(&zones).as_deref(), Some("aaaa"));
+
+        fs::write(git_dir.join("refs/heads/main"), "bbbb\n").unwrap();
+        assert_eq!(head_commit(&zones).as_deref(), Some("bbbb"));
+
+        fs::write(git_dir.join("HEAD"), "cccc\n").unwrap();
+        assert_eq!(head_commit(&zones).as_deref(), Some("cccc"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
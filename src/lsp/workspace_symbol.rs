@@ -0,0 +1,355 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lsp_types::{Location, Position, Range, SymbolInformation, SymbolKind};
+
+use crate::bind::{obj::ObjRef, parser::IscParser};
+use crate::check::zones::zone_configs;
+use crate::config::ProjectConfig;
+use crate::path::PathResolver;
+
+use super::path_to_uri;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfSymbolKind {
+    Zone,
+    View,
+    Acl,
+    Key,
+    DnssecPolicy,
+    Tls,
+}
+
+impl ConfSymbolKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            ConfSymbolKind::Zone => "zone",
+            ConfSymbolKind::View => "view",
+            ConfSymbolKind::Acl => "acl",
+            ConfSymbolKind::Key => "key",
+            ConfSymbolKind::DnssecPolicy => "dnssec-policy",
+            ConfSymbolKind::Tls => "tls",
+        }
+    }
+
+    fn lsp_kind(&self) -> SymbolKind {
+        match self {
+            ConfSymbolKind::Zone => SymbolKind::NAMESPACE,
+            ConfSymbolKind::View => SymbolKind::MODULE,
+            ConfSymbolKind::Acl => SymbolKind::ARRAY,
+            ConfSymbolKind::Key => SymbolKind::KEY,
+            ConfSymbolKind::DnssecPolicy => SymbolKind::STRUCT,
+            ConfSymbolKind::Tls => SymbolKind::STRUCT,
+        }
+    }
+}
+
+/// A named statement found in a parsed configuration.
+#[derive(Debug, Clone)]
+pub struct ConfSymbol {
+    pub name: String,
+    pub kind: ConfSymbolKind,
+    pub container: Option<String>,
+    pub file: PathBuf,
+    pub range: Range,
+}
+
+/// Collects every zone, view, acl, key, dnssec-policy and tls statement
+/// of a parsed configuration, including the ones nested in views.
+pub fn collect(root: ObjRef) -> Vec<ConfSymbol> {
+    let mut lines = HashMap::new();
+    let mut symbols = vec![];
+
+    collect_map(root, None, &mut lines, &mut symbols);
+
+    for view in root.map_get("view").map(|v| v.list()).unwrap_or_default() {
+        let Some(view_symbol) = symbol(view, ConfSymbolKind::View, None, &mut lines) else {
+            continue;
+        };
+
+        let container = format!("view {}", view_symbol.name);
+        symbols.push(view_symbol);
+        if let Some(options) = view.tuple_get("options") {
+            collect_map(options, Some(&container), &mut lines, &mut symbols);
+        }
+    }
+
+    symbols
+}
+
+fn collect_map(
+    map: ObjRef,
+    container: Option<&str>,
+    lines: &mut HashMap<PathBuf, Vec<String>>,
+    symbols: &mut Vec<ConfSymbol>,
+) {
+    let kinds = [
+        ConfSymbolKind::Acl,
+        ConfSymbolKind::Key,
+        ConfSymbolKind::DnssecPolicy,
+        ConfSymbolKind::Tls,
+        ConfSymbolKind::Zone,
+    ];
+
+    for kind in kinds {
+        for obj in map
+            .map_get(kind.keyword())
+            .map(|v| v.list())
+            .unwrap_or_default()
+        {
+            if let Some(symbol) = symbol(obj, kind, container, lines) {
+                symbols.push(symbol);
+            }
+        }
+    }
+}
+
+fn symbol(
+    obj: ObjRef,
+    kind: ConfSymbolKind,
+    container: Option<&str>,
+    lines: &mut HashMap<PathBuf, Vec<String>>,
+) -> Option<ConfSymbol> {
+    // zone, view and acl are tuples, the others are named maps
+    let name = match kind {
+        ConfSymbolKind::Zone | ConfSymbolKind::View | ConfSymbolKind::Acl => {
+            obj.tuple_get("name")?.as_str()?
+        }
+        _ => obj.map_name()?.as_str()?,
+    };
+
+    let file = obj.file()?;
+    let file = fs::canonicalize(file).unwrap_or_else(|_| PathBuf::from(file));
+    let line = obj.line().saturating_sub(1);
+
+    let text = lines.entry(file.clone()).or_insert_with(|| {
+        fs::read_to_string(&file)
+            .map(|s| s.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    });
+    let (start, end) = text
+        .get(line as usize)
+        .and_then(|l| {
+            l.find(name)
+                .map(|i| (l[..i].chars().count(), name.chars().count()))
+        })
+        .map(|(start, len)| (start as u32, (start + len) as u32))
+        .unwrap_or((0, 0));
+
+    Some(ConfSymbol {
+        name: name.to_string(),
+        kind,
+        container: container.map(str::to_string),
+        file,
+        range: Range {
+            start: Position {
+                line,
+                character: start,
+            },
+            end: Position {
+                line,
+                character: end,
+            },
+        },
+    })
+}
+
+/// Symbols of every `named.conf` found in the workspace folders, keyed by
+/// the root file they were reached from.
+#[derive(Default)]
+pub struct WorkspaceIndex {
+    folders: Vec<PathBuf>,
+    roots: HashMap<PathBuf, Vec<ConfSymbol>>,
+    /// By canonical path, the resolver of the configuration that loads
+    /// each zone file.
+    zone_resolvers: HashMap<PathBuf, PathResolver>,
+}
+
+impl WorkspaceIndex {
+    pub fn new(folders: Vec<PathBuf>) -> Self {
+        WorkspaceIndex {
+            folders,
+            roots: HashMap::new(),
+            zone_resolvers: HashMap::new(),
+        }
+    }
+
+    /// Re-discovers the root configs and parses each of them again. A root
+    /// that fails to parse keeps the symbols of its last good parse.
+    pub fn rebuild(&mut self, parser: &IscParser, config: &ProjectConfig) {
+        let mut roots = config
+            .roots()
+            .into_iter()
+            .filter_map(|root| fs::canonicalize(root).ok())
+            .collect::<Vec<_>>();
+        if config.roots.is_empty() {
+            for folder in &self.folders {
+                find_roots(folder, &mut roots);
+            }
+        }
+
+        self.roots.retain(|root, _| roots.contains(root));
+        self.zone_resolvers.clear();
+        for root in roots {
+            let resolver = config.resolver(&root);
+            if let Ok(obj) = parser.parse_file_with(&root, &resolver) {
+                let resolver = resolver.with_config(obj.root());
+                for zone in zone_configs(obj.root(), &resolver) {
+                    let Some(file) = zone.file else {
+                        continue;
+                    };
+                    let file = fs::canonicalize(&file).unwrap_or(file);
+                    self.zone_resolvers.insert(file, resolver.clone());
+                }
+                self.roots.insert(root, collect(obj.root()));
+            }
+        }
+    }
+
+    /// The resolver of the configuration that loads the zone file at
+    /// `path`, which is where `named` finds the files it includes.
+    pub fn zone_resolver(&self, path: &Path) -> Option<&PathResolver> {
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.zone_resolvers.get(&path)
+    }
+
+    pub fn symbols(&self) -> impl Iterator<Item = &ConfSymbol> {
+        let mut seen = HashSet::new();
+        self.roots
+            .values()
+            .flatten()
+            .filter(move |s| seen.insert((s.file.clone(), s.range.start, s.kind, s.name.clone())))
+    }
+
+    #[allow(deprecated)]
+    pub fn query(&self, query: &str) -> Vec<SymbolInformation> {
+        let mut matches = self
+            .symbols()
+            .filter_map(|s| fuzzy_score(query, &s.name).map(|score| (score, s)))
+            .collect::<Vec<_>>();
+        matches.sort_by(|(a, x), (b, y)| b.cmp(a).then_with(|| x.name.cmp(&y.name)));
+
+        matches
+            .into_iter()
+            .filter_map(|(_, s)| {
+                Some(SymbolInformation {
+                    name: format!("{} {}", s.kind.keyword(), s.name),
+                    kind: s.kind.lsp_kind(),
+                    tags: None,
+                    deprecated: None,
+                    location: Location {
+                        uri: path_to_uri(&s.file)?,
+                        range: s.range,
+                    },
+                    container_name: s.container.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+fn find_roots(dir: &Path, roots: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if path.is_dir() {
+            if !name.starts_with('.') && name != "target" {
+                find_roots(&path, roots);
+            }
+        } else if name == "named.conf" {
+            if let Ok(path) = fs::canonicalize(path) {
+                roots.push(path);
+            }
+        }
+    }
+}
+
+/// Scores `name` against `query` as a case-insensitive subsequence match,
+/// favouring consecutive characters and matches at label boundaries.
+/// Returns `None` if `query` is not a subsequence of `name`.
+pub fn fuzzy_score(query: &str, name: &str) -> Option<i64> {
+    let name = name.to_ascii_lowercase().chars().collect::<Vec<_>>();
+    let query = query.to_ascii_lowercase();
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut last: Option<usize> = None;
+    for q in query.chars().filter(|c| !c.is_whitespace()) {
+        let i = next + name[next..].iter().position(|&c| c == q)?;
+
+        score += 1;
+        if last.is_some_and(|last| last + 1 == i) {
+            score += 5;
+        }
+        if i == 0 || matches!(name[i - 1], '.' | '-' | '_' | '/') {
+            score += 8;
+        }
+
+        last = Some(i);
+        next = i + 1;
+    }
+
+    if query.chars().count() == name.len() && last.is_some() {
+        score += 100;
+    }
+
+    Some(score - name.len() as i64 / 8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bind::{log::IscLog, mem::IscMem};
+
+    #[test]
+    fn test_collect() {
+        let mem = IscMem::new();
+        let log = IscLog::new(&mem);
+        let parser = IscParser::new(&mem, &log).unwrap();
+
+        let obj = parser
+            .parse_string(
+                r#"
+acl trusted { 10.0.0.0/8; };
+key "xfer-key" { algorithm hmac-sha256; secret "aGVsbG8="; };
+view "internal" {
+    zone "corp.example" { type primary; file "corp.example.db"; };
+};
+"#,
+            )
+            .unwrap();
+
+        let symbols = collect(obj.root());
+        let names = symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_str(), s.container.as_deref()))
+            .collect::<Vec<_>>();
+
+        assert!(names.contains(&(ConfSymbolKind::Acl, "trusted", None)));
+        assert!(names.contains(&(ConfSymbolKind::Key, "xfer-key", None)));
+        assert!(names.contains(&(ConfSymbolKind::View, "internal", None)));
+        assert!(names.contains(&(ConfSymbolKind::Zone, "corp.example", Some("view internal"))));
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("corp", "corp.example").is_some());
+        assert!(fuzzy_score("cex", "corp.example").is_some());
+        assert!(fuzzy_score("xc", "corp.example").is_none());
+        assert!(fuzzy_score("", "corp.example").is_some());
+
+        let exact = fuzzy_score("corp.example", "corp.example").unwrap();
+        let prefix = fuzzy_score("corp", "corp.example").unwrap();
+        let scattered = fuzzy_score("crp", "corp.example").unwrap();
+        assert!(exact > prefix);
+        assert!(prefix > scattered);
+    }
+}
@@ -0,0 +1,423 @@
+//! Mapping paths in a configuration to files on this machine.
+//!
+//! `named` resolves relative paths against `options { directory }` and,
+//! when started with `-t`, sees every path inside a chroot. A checkout of
+//! the configuration keeps those files elsewhere, so paths are resolved
+//! through a [`PathResolver`] before they are opened, applying the path
+//! mappings of the project configuration last.
+
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, DirBuilder},
+    io,
+    os::unix::fs::DirBuilderExt,
+    path::{Component, Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::bind::obj::ObjRef;
+use crate::syntax::{
+    byte_offset,
+    conf::{self, Statement, TokenKind},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathResolver {
+    /// The directory of the main configuration file.
+    base: PathBuf,
+    /// The root `named` is chrooted to, as with `named -t`.
+    chroot: Option<PathBuf>,
+    /// `options { directory }` as written in the configuration.
+    directory: Option<PathBuf>,
+    /// Prefixes of deployed paths and the local directories they are
+    /// kept in.
+    mappings: Vec<(PathBuf, PathBuf)>,
+}
+
+impl PathResolver {
+    /// Resolves paths of the configuration at `conf_path`. Relative paths
+    /// are taken relative to its directory until a `directory` is set.
+    pub fn new(conf_path: &Path) -> Self {
+        PathResolver {
+            base: conf_path.parent().unwrap_or(Path::new(".")).to_path_buf(),
+            chroot: None,
+            directory: None,
+            mappings: vec![],
+        }
+    }
+
+    /// Maps absolute paths below `chroot`.
+    pub fn with_chroot(mut self, chroot: impl Into<PathBuf>) -> Self {
+        self.chroot = Some(chroot.into());
+        self
+    }
+
+    /// Maps deployed paths starting with the first path of a pair below the
+    /// second. The longest matching prefix wins.
+    pub fn with_mappings(mut self, mappings: Vec<(PathBuf, PathBuf)>) -> Self {
+        self.mappings = mappings;
+        self
+    }
+
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Self {
+        self.directory = Some(directory.into());
+        self
+    }
+
+    /// Takes `options { directory }` from a parsed configuration.
+    pub fn with_config(self, root: ObjRef) -> Self {
+        let directory = root
+            .map_get("options")
+            .and_then(|o| o.map_get("directory"))
+            .and_then(|d| d.as_str());
+
+        match directory {
+            Some(directory) => self.with_directory(directory),
+            None => self,
+        }
+    }
+
+    /// Takes `options { directory }` from configuration text, for resolving
+    /// `include` paths before the configuration can be parsed.
+    pub fn with_text(self, text: &str) -> Self {
+        let conf = conf::parse(text);
+        let directory = conf
+            .statements
+            .iter()
+            .filter(|s| s.name() == Some("options"))
+            .filter_map(|s| s.block.as_ref())
+            .flat_map(|b| &b.statements)
+            .find(|s| s.name() == Some("directory"))
+            .and_then(|s| s.words.get(1))
+            .map(|w| w.value().to_string());
+
+        match directory {
+            Some(directory) => self.with_directory(directory),
+            None => self,
+        }
+    }
+
+    pub fn chroot(&self) -> Option<&Path> {
+        self.chroot.as_deref()
+    }
+
+    /// The working directory of `named`, on this machine.
+    pub fn directory(&self) -> PathBuf {
+        match &self.directory {
+            Some(directory) if directory.is_absolute() => self.map_root(directory),
+            Some(directory) => self.base.join(directory),
+            None => self.base.clone(),
+        }
+    }
+
+    /// The file on this machine a path of the configuration refers to.
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        if path.is_absolute() {
+            self.map_root(path)
+        } else {
+            self.directory().join(path)
+        }
+    }
+
+    fn map_root(&self, path: &Path) -> PathBuf {
+        let deployed = match &self.chroot {
+            Some(chroot) => chroot.join(
+                path.components()
+                    .filter(|c| !matches!(c, Component::RootDir | Component::Prefix(_)))
+                    .collect::<PathBuf>(),
+            ),
+            None => path.to_path_buf(),
+        };
+
+        let mapping = self
+            .mappings
+            .iter()
+            .filter(|(prefix, _)| deployed.starts_with(prefix))
+            .max_by_key(|(prefix, _)| prefix.components().count());
+        match mapping {
+            Some((prefix, local)) => match deployed.strip_prefix(prefix) {
+                Ok(rest) if rest.as_os_str().is_empty() => local.clone(),
+                Ok(rest) => local.join(rest),
+                Err(_) => deployed,
+            },
+            None => deployed,
+        }
+    }
+}
+
+/// A configuration with the includes of every file it includes resolved
+/// through a [`PathResolver`].
+///
+/// libisccfg opens included files itself, as written. An included file
+/// with includes of its own is therefore copied with those rewritten, and
+/// the copy is included instead. Only the quoted paths change, so line
+/// numbers stay those of the original files, and [`IncludedFiles::original`]
+/// maps the paths of copies back. The copies are removed on drop.
+#[derive(Debug)]
+pub struct IncludedFiles {
+    /// The main configuration with its includes rewritten.
+    text: String,
+    /// The private directory holding the copies, once one is made.
+    dir: Option<PathBuf>,
+    /// The original of each copy, by the path of the copy.
+    originals: HashMap<String, String>,
+}
+
+impl IncludedFiles {
+    /// Reads the configuration at `path` and resolves its includes.
+    pub fn read(path: &Path, resolver: &PathResolver) -> io::Result<Self> {
+        IncludedFiles::new(&fs::read_to_string(path)?, resolver)
+    }
+
+    /// Resolves the includes of configuration `text`, using the
+    /// `directory` set in its own `options` when there is one.
+    pub fn new(text: &str, resolver: &PathResolver) -> io::Result<Self> {
+        let resolver = resolver.clone().with_text(text);
+        let mut files = IncludedFiles {
+            text: String::new(),
+            dir: None,
+            originals: HashMap::new(),
+        };
+        files.text = files.rewrite(text, &resolver, &mut vec![])?;
+
+        Ok(files)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// The file a path recorded by libisccfg stands for: the original of a
+    /// copy, or the path itself.
+    pub fn original<'s>(&'s self, file: &'s str) -> &'s str {
+        self.originals.get(file).map_or(file, String::as_str)
+    }
+
+    pub fn originals(&self) -> &HashMap<String, String> {
+        &self.originals
+    }
+
+    /// BIND's log with the paths of copies replaced by their originals.
+    pub fn restore(&self, log: &str) -> String {
+        self.originals
+            .iter()
+            .fold(log.to_string(), |log, (copy, original)| {
+                log.replace(copy.as_str(), original)
+            })
+    }
+
+    /// Rewrites the include paths of `text`, from the end so that earlier
+    /// offsets stay valid. `stack` holds the files being rewritten.
+    fn rewrite(
+        &mut self,
+        text: &str,
+        resolver: &PathResolver,
+        stack: &mut Vec<PathBuf>,
+    ) -> io::Result<String> {
+        let conf = conf::parse(text);
+        let mut paths = vec![];
+        collect_includes(&conf.statements, &mut paths);
+
+        let mut rewritten = text.to_string();
+        for token in paths.into_iter().rev() {
+            let (Some(start), Some(end)) =
+                (byte_offset(text, token.start), byte_offset(text, token.end))
+            else {
+                continue;
+            };
+            let path = self.include(token.value(), resolver, stack)?;
+            let quoted = format!("\"{}\"", path.display().to_string().replace('"', "\\\""));
+            rewritten.replace_range(start..end, &quoted);
+        }
+
+        Ok(rewritten)
+    }
+
+    /// The file to include for `path`: the file it resolves to, or a copy
+    /// of it when it has includes of its own.
+    fn include(
+        &mut self,
+        path: &str,
+        resolver: &PathResolver,
+        stack: &mut Vec<PathBuf>,
+    ) -> io::Result<PathBuf> {
+        let resolved = resolver.resolve(path);
+        // glob patterns and missing files are left to libisccfg, and so is
+        // a file that includes itself
+        let Ok(text) = fs::read_to_string(&resolved) else {
+            return Ok(resolved);
+        };
+        let conf = conf::parse(&text);
+        let mut nested = vec![];
+        collect_includes(&conf.statements, &mut nested);
+        if nested.is_empty() || stack.contains(&resolved) {
+            return Ok(resolved);
+        }
+
+        stack.push(resolved.clone());
+        let rewritten = self.rewrite(&text, resolver, stack)?;
+        stack.pop();
+
+        let dir = match &self.dir {
+            Some(dir) => dir.clone(),
+            None => self.dir.insert(private_dir()?).clone(),
+        };
+        let copy = dir.join(format!("include-{}.conf", self.originals.len()));
+        fs::write(&copy, rewritten)?;
+        self.originals
+            .insert(copy.display().to_string(), resolved.display().to_string());
+
+        Ok(copy)
+    }
+}
+
+impl Drop for IncludedFiles {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+/// A new directory in the temporary directory that only this user can
+/// access. Creating it fails rather than reuse anything already there.
+fn private_dir() -> io::Result<PathBuf> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .subsec_nanos();
+
+    let mut attempt = 0;
+    loop {
+        let name = format!("bls-includes-{}-{}-{}", process::id(), nanos, attempt);
+        let dir = env::temp_dir().join(name);
+        match DirBuilder::new().mode(0o700).create(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists && attempt < 16 => attempt += 1,
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn collect_includes<'a>(statements: &'a [Statement], paths: &mut Vec<&'a conf::Token>) {
+    for statement in statements {
+        if statement.name() == Some("include") {
+            if let Some(path) = statement
+                .words
+                .get(1)
+                .filter(|w| w.kind == TokenKind::Quoted)
+            {
+                paths.push(path);
+            }
+        }
+        if let Some(block) = &statement.block {
+            collect_includes(&block.statements, paths);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let resolver = PathResolver::new(Path::new("/repo/etc/named.conf"));
+        assert_eq!(
+            resolver.resolve("db.example"),
+            Path::new("/repo/etc/db.example")
+        );
+        assert_eq!(
+            resolver.resolve("/var/named/db"),
+            Path::new("/var/named/db")
+        );
+
+        let resolver = resolver
+            .with_chroot("/repo/chroot")
+            .with_directory("/var/named");
+        assert_eq!(
+            resolver.resolve("db.example"),
+            Path::new("/repo/chroot/var/named/db.example")
+        );
+        assert_eq!(
+            resolver.resolve("/etc/bind/keys"),
+            Path::new("/repo/chroot/etc/bind/keys")
+        );
+
+        let resolver = PathResolver::new(Path::new("/repo/etc/named.conf")).with_directory("zones");
+        assert_eq!(resolver.directory(), Path::new("/repo/etc/zones"));
+
+        let resolver = PathResolver::new(Path::new("/repo/etc/named.conf"))
+            .with_directory("/var/named")
+            .with_mappings(vec![
+                (PathBuf::from("/etc"), PathBuf::from("/repo/etc")),
+                (PathBuf::from("/etc/bind/keys"), PathBuf::from("/repo/keys")),
+                (PathBuf::from("/var/named"), PathBuf::from("/repo/zones")),
+            ]);
+        assert_eq!(
+            resolver.resolve("db.example"),
+            Path::new("/repo/zones/db.example")
+        );
+        assert_eq!(
+            resolver.resolve("/etc/rndc.key"),
+            Path::new("/repo/etc/rndc.key")
+        );
+        assert_eq!(resolver.resolve("/etc/bind/keys"), Path::new("/repo/keys"));
+        assert_eq!(resolver.resolve("/etc2/x"), Path::new("/etc2/x"));
+    }
+
+    #[test]
+    fn test_resolve_includes() {
+        let text = "options {\n\tdirectory \"/var/named\";\n};\n\
+                    include \"/etc/bind/keys.conf\"; // keys\n\
+                    view \"v\" { include \"views/v.conf\"; };\n";
+        let resolver = PathResolver::new(Path::new("/repo/named.conf")).with_chroot("/repo/root");
+
+        let included = IncludedFiles::new(text, &resolver).unwrap();
+        assert_eq!(
+            included.text(),
+            "options {\n\tdirectory \"/var/named\";\n};\n\
+             include \"/repo/root/etc/bind/keys.conf\"; // keys\n\
+             view \"v\" { include \"/repo/root/var/named/views/v.conf\"; };\n"
+        );
+        assert!(included.originals().is_empty());
+    }
+
+    #[test]
+    fn test_nested_includes() {
+        let dir = std::env::temp_dir().join(format!("bls-test-includes-{}", std::process::id()));
+        fs::create_dir_all(dir.join("root/etc/bind")).unwrap();
+        let keys = dir.join("root/etc/bind/keys.conf");
+        let rndc = dir.join("root/etc/bind/rndc.key");
+        fs::write(&keys, "// keys\ninclude \"/etc/bind/rndc.key\";\n").unwrap();
+        fs::write(&rndc, "key \"rndc\" { };\n").unwrap();
+
+        let text = "include \"/etc/bind/keys.conf\";\n";
+        let resolver = PathResolver::new(&dir.join("named.conf")).with_chroot(dir.join("root"));
+        let included = IncludedFiles::new(text, &resolver).unwrap();
+
+        let (copy, original) = included.originals().iter().next().unwrap();
+        assert_eq!(included.originals().len(), 1);
+        assert_eq!(original, &keys.display().to_string());
+        assert_eq!(included.text(), format!("include \"{}\";\n", copy));
+        assert_eq!(
+            fs::read_to_string(copy).unwrap(),
+            format!("// keys\ninclude \"{}\";\n", rndc.display())
+        );
+
+        assert_eq!(included.original(copy), original);
+        assert_eq!(included.original("named.conf"), "named.conf");
+        assert_eq!(
+            included.restore(&format!("{}:2: unknown option 'x'", copy)),
+            format!("{}:2: unknown option 'x'", keys.display())
+        );
+
+        let copies = Path::new(copy).parent().unwrap().to_path_buf();
+        drop(included);
+        assert!(!copies.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
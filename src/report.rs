@@ -0,0 +1,224 @@
+//! Machine-readable forms of diagnostics for `bls check`: a plain JSON list
+//! and SARIF 2.1.0 for code review platforms.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::check::lint::{rule, Category};
+use crate::diagnostic::{Diagnostic, Fix, Severity, LINE_END};
+use crate::lsp::{encode_path, file_uri};
+use crate::syntax::Pos;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The diagnostics as a JSON array, with 1-based lines and columns. Whole
+/// line diagnostics have no columns.
+pub fn to_json(diagnostics: &[Diagnostic]) -> serde_json::Result<String> {
+    let position = |pos: Pos| {
+        json!({
+            "line": pos.line + 1,
+            "column": (pos.column != LINE_END).then_some(pos.column + 1),
+        })
+    };
+    let range = |start: Pos, end: Pos| json!({ "start": position(start), "end": position(end) });
+
+    let diagnostics = diagnostics
+        .iter()
+        .map(|d| {
+            let fixes = d.fixes.iter().map(|fix| {
+                let edits = fix.edits.iter().map(|edit| {
+                    json!({
+                        "range": range(edit.start, edit.end),
+                        "new_text": edit.new_text,
+                    })
+                });
+                json!({ "title": fix.title, "edits": edits.collect::<Vec<_>>() })
+            });
+
+            json!({
+                "file": d.file,
+                "range": range(d.start, d.end),
+                "severity": d.severity.name(),
+                "code": d.code,
+                "source": d.source,
+                "message": d.message,
+                "fixes": fixes.collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&diagnostics)
+}
+
+/// The diagnostics as a SARIF log with a single run. BIND's own messages
+/// have no code and are reported under the rule `bind`.
+pub fn to_sarif(diagnostics: &[Diagnostic]) -> serde_json::Result<String> {
+    let mut rules = diagnostics.iter().map(rule_id).collect::<Vec<_>>();
+    rules.sort();
+    rules.dedup();
+
+    let results = diagnostics
+        .iter()
+        .map(|d| {
+            let uri = artifact_uri(&d.file);
+            let fixes = d.fixes.iter().map(|fix| sarif_fix(&uri, fix));
+
+            json!({
+                "ruleId": rule_id(d),
+                "ruleIndex": rules.binary_search(&rule_id(d)).ok(),
+                "level": level(d.severity),
+                "message": { "text": d.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                        "region": region(d.start, d.end),
+                    },
+                }],
+                "fixes": fixes.collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "bls",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules.iter().map(|id| sarif_rule(id)).collect::<Vec<_>>(),
+                },
+            },
+            "columnKind": "utf16CodeUnits",
+            "results": results,
+        }],
+    });
+
+    serde_json::to_string_pretty(&log)
+}
+
+/// A rule of the run, described when it is one of the lint rules.
+fn sarif_rule(id: &str) -> Value {
+    match rule(id) {
+        Some(rule) => json!({
+            "id": id,
+            "shortDescription": { "text": rule.description },
+            "defaultConfiguration": { "level": level(rule.severity) },
+            "properties": {
+                "tags": match rule.category {
+                    Category::Security => vec!["security"],
+                    Category::Practice => vec![],
+                },
+            },
+        }),
+        None => json!({ "id": id }),
+    }
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info | Severity::Hint => "note",
+    }
+}
+
+fn rule_id(diagnostic: &Diagnostic) -> &str {
+    diagnostic.code.as_deref().unwrap_or(diagnostic.source)
+}
+
+/// Relative paths stay relative, to the directory `bls check` ran in.
+fn artifact_uri(path: &Path) -> String {
+    if path.is_absolute() {
+        file_uri(path)
+    } else {
+        encode_path(path)
+    }
+}
+
+/// A SARIF region. End columns are exclusive, as are those of diagnostics,
+/// and whole lines have no columns.
+fn region(start: Pos, end: Pos) -> Value {
+    if end.column == LINE_END {
+        return json!({ "startLine": start.line + 1, "endLine": end.line + 1 });
+    }
+
+    json!({
+        "startLine": start.line + 1,
+        "startColumn": start.column + 1,
+        "endLine": end.line + 1,
+        "endColumn": end.column + 1,
+    })
+}
+
+fn sarif_fix(uri: &str, fix: &Fix) -> Value {
+    let replacements = fix.edits.iter().map(|edit| {
+        json!({
+            "deletedRegion": region(edit.start, edit.end),
+            "insertedContent": { "text": edit.new_text },
+        })
+    });
+
+    json!({
+        "description": { "text": fix.title },
+        "artifactChanges": [{
+            "artifactLocation": { "uri": uri },
+            "replacements": replacements.collect::<Vec<_>>(),
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::Edit;
+
+    #[test]
+    fn test_sarif() {
+        let pos = |line, column| Pos { line, column };
+        let mut lint = Diagnostic::new(
+            Path::new("db.example"),
+            pos(2, 4),
+            pos(2, 8),
+            Severity::Warning,
+            "serial-unchanged",
+            "serial unchanged".to_string(),
+        );
+        lint.fixes.push(Fix {
+            title: "Increment".to_string(),
+            edits: vec![Edit::new((pos(2, 4), pos(2, 8)), "2")],
+            preferred: true,
+        });
+        let bind = Diagnostic::line(
+            Path::new("/etc/bind #1/named.conf"),
+            0,
+            Severity::Error,
+            "unknown option 'foo'".to_string(),
+        );
+
+        let log: Value = serde_json::from_str(&to_sarif(&[lint, bind]).unwrap()).unwrap();
+        let results = &log["runs"][0]["results"];
+        assert_eq!(log["runs"][0]["tool"]["driver"]["rules"][0]["id"], "bind");
+
+        assert_eq!(results[0]["ruleId"], "serial-unchanged");
+        assert_eq!(results[0]["ruleIndex"], 1);
+        let region = &results[0]["locations"][0]["physicalLocation"]["region"];
+        assert_eq!(
+            *region,
+            json!({ "startLine": 3, "startColumn": 5, "endLine": 3, "endColumn": 9 })
+        );
+        let change = &results[0]["fixes"][0]["artifactChanges"][0];
+        assert_eq!(change["artifactLocation"]["uri"], "db.example");
+        assert_eq!(change["replacements"][0]["insertedContent"]["text"], "2");
+
+        assert_eq!(results[1]["level"], "error");
+        let location = &results[1]["locations"][0]["physicalLocation"];
+        assert_eq!(
+            location["artifactLocation"]["uri"],
+            "file:///etc/bind%20%231/named.conf"
+        );
+        assert_eq!(location["region"], json!({ "startLine": 1, "endLine": 1 }));
+    }
+}
@@ -0,0 +1,300 @@
+//! A position-preserving reader for `named.conf` style files.
+//!
+//! The result is a loose statement tree that tolerates errors; it is meant
+//! for editor features, while [`crate::bind::parser::IscParser`] remains the
+//! authority on what is valid.
+
+use super::{Cursor, Pos};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    Quoted,
+    OpenBrace,
+    CloseBrace,
+    Semicolon,
+    Bang,
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Token {
+    /// The token text without surrounding quotes.
+    pub fn value(&self) -> &str {
+        match self.kind {
+            TokenKind::Quoted => self
+                .text
+                .strip_prefix('"')
+                .map(|s| s.strip_suffix('"').unwrap_or(s))
+                .unwrap_or(&self.text),
+            _ => &self.text,
+        }
+    }
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut cursor = Cursor::new(text);
+
+    while let Some(c) = cursor.peek() {
+        let start = cursor.pos();
+        let offset = cursor.offset();
+
+        let kind = match (c, cursor.peek_second()) {
+            (c, _) if c.is_whitespace() => {
+                cursor.bump();
+                continue;
+            }
+            ('#', _) | ('/', Some('/')) => {
+                while cursor.peek().is_some_and(|c| c != '\n') {
+                    cursor.bump();
+                }
+                TokenKind::Comment
+            }
+            ('/', Some('*')) => {
+                cursor.bump();
+                cursor.bump();
+                while let Some(c) = cursor.bump() {
+                    if c == '*' && cursor.peek() == Some('/') {
+                        cursor.bump();
+                        break;
+                    }
+                }
+                TokenKind::Comment
+            }
+            ('{', _) => {
+                cursor.bump();
+                TokenKind::OpenBrace
+            }
+            ('}', _) => {
+                cursor.bump();
+                TokenKind::CloseBrace
+            }
+            (';', _) => {
+                cursor.bump();
+                TokenKind::Semicolon
+            }
+            ('!', _) => {
+                cursor.bump();
+                TokenKind::Bang
+            }
+            ('"', _) => {
+                cursor.bump();
+                while let Some(c) = cursor.bump() {
+                    match c {
+                        '\\' => {
+                            cursor.bump();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                TokenKind::Quoted
+            }
+            _ => {
+                while let Some(c) = cursor.peek() {
+                    if c.is_whitespace()
+                        || matches!(c, '{' | '}' | ';' | '"' | '!')
+                        || (c == '/' && matches!(cursor.peek_second(), Some('/' | '*')))
+                    {
+                        break;
+                    }
+                    cursor.bump();
+                }
+                TokenKind::Word
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            text: cursor.slice(offset).to_string(),
+            start,
+            end: cursor.pos(),
+        });
+    }
+
+    tokens
+}
+
+/// A statement such as `recursion yes;` or `zone "example" { ... };`.
+#[derive(Debug, Clone)]
+pub struct Statement {
+    /// Words, strings and `!` of the statement, including any that follow
+    /// the block.
+    pub words: Vec<Token>,
+    pub block: Option<Block>,
+    pub semicolon: Option<Token>,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Statement {
+    /// The clause name, e.g. `zone` or `allow-query`.
+    pub fn name(&self) -> Option<&str> {
+        self.words
+            .first()
+            .filter(|t| t.kind == TokenKind::Word)
+            .map(|t| t.text.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub open: Token,
+    pub close: Option<Token>,
+    pub statements: Vec<Statement>,
+}
+
+#[derive(Debug, Default)]
+pub struct ConfText {
+    pub tokens: Vec<Token>,
+    pub statements: Vec<Statement>,
+}
+
+pub fn parse(text: &str) -> ConfText {
+    let tokens = lex(text);
+    let mut code = tokens
+        .iter()
+        .filter(|t| t.kind != TokenKind::Comment)
+        .peekable();
+
+    let mut statements = vec![];
+    while code.peek().is_some() {
+        if let Some(statement) = parse_statement(&mut code) {
+            statements.push(statement);
+        }
+    }
+
+    ConfText { tokens, statements }
+}
+
+fn parse_statement<'a, I>(tokens: &mut std::iter::Peekable<I>) -> Option<Statement>
+where
+    I: Iterator<Item = &'a Token>,
+{
+    let first = (*tokens.peek()?).clone();
+    let mut statement = Statement {
+        words: vec![],
+        block: None,
+        semicolon: None,
+        start: first.start,
+        end: first.end,
+    };
+
+    while let Some(&token) = tokens.peek() {
+        match token.kind {
+            TokenKind::Semicolon => {
+                tokens.next();
+                statement.end = token.end;
+                statement.semicolon = Some(token.clone());
+                break;
+            }
+            TokenKind::CloseBrace => {
+                // belongs to the enclosing block
+                if statement.words.is_empty() && statement.block.is_none() {
+                    tokens.next();
+                    return None;
+                }
+                break;
+            }
+            TokenKind::OpenBrace if statement.block.is_none() => {
+                tokens.next();
+                let mut block = Block {
+                    open: token.clone(),
+                    close: None,
+                    statements: vec![],
+                };
+
+                while let Some(&next) = tokens.peek() {
+                    if next.kind == TokenKind::CloseBrace {
+                        tokens.next();
+                        statement.end = next.end;
+                        block.close = Some(next.clone());
+                        break;
+                    }
+                    if let Some(inner) = parse_statement(tokens) {
+                        statement.end = inner.end;
+                        block.statements.push(inner);
+                    }
+                }
+
+                statement.block = Some(block);
+            }
+            TokenKind::OpenBrace => {
+                // a second block without a separating ';'
+                break;
+            }
+            _ => {
+                tokens.next();
+                statement.end = token.end;
+                statement.words.push(token.clone());
+            }
+        }
+    }
+
+    Some(statement)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lex() {
+        let tokens = lex("acl \"a\" { !10.0.0.0/8; }; // c\n/* x\ny */ # z\n");
+        let kinds = tokens.iter().map(|t| t.kind).collect::<Vec<_>>();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Word,
+                TokenKind::Quoted,
+                TokenKind::OpenBrace,
+                TokenKind::Bang,
+                TokenKind::Word,
+                TokenKind::Semicolon,
+                TokenKind::CloseBrace,
+                TokenKind::Semicolon,
+                TokenKind::Comment,
+                TokenKind::Comment,
+                TokenKind::Comment,
+            ]
+        );
+        assert_eq!(tokens[4].text, "10.0.0.0/8");
+        assert_eq!(tokens[9].end, Pos { line: 2, column: 4 });
+    }
+
+    #[test]
+    fn test_parse() {
+        let conf = parse(
+            r#"
+options {
+    directory "/var/named";
+    listen-on port 53 { 127.0.0.1; };
+};
+zone "example.com" { type primary; file "example.com.db"; };
+"#,
+        );
+
+        let names = conf
+            .statements
+            .iter()
+            .map(|s| s.name().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["options", "zone"]);
+
+        let options = conf.statements[0].block.as_ref().unwrap();
+        assert_eq!(options.statements.len(), 2);
+        assert_eq!(options.statements[1].words.len(), 3);
+        assert!(options.statements[1].block.is_some());
+        assert_eq!(conf.statements[0].end.line, 4);
+        assert_eq!(conf.statements[1].words[1].value(), "example.com");
+    }
+}
@@ -0,0 +1,79 @@
+pub mod conf;
+pub mod zone;
+
+/// A position in a document. Lines are 0-based and columns are counted in
+/// UTF-16 code units, matching what LSP clients expect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pos {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// The byte offset of `pos` in `text`.
+pub(crate) fn byte_offset(text: &str, pos: Pos) -> Option<usize> {
+    let lines = text.split_inclusive('\n').collect::<Vec<_>>();
+    let line = lines.get(pos.line as usize)?;
+    let start = lines[..pos.line as usize]
+        .iter()
+        .map(|l| l.len())
+        .sum::<usize>();
+
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units == pos.column {
+            return Some(start + i);
+        }
+        units += c.len_utf16() as u32;
+    }
+
+    (units == pos.column).then_some(start + line.len())
+}
+
+pub(crate) struct Cursor<'a> {
+    text: &'a str,
+    offset: usize,
+    pos: Pos,
+}
+
+impl<'a> Cursor<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Cursor {
+            text,
+            offset: 0,
+            pos: Pos::default(),
+        }
+    }
+
+    pub fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    pub fn peek_second(&self) -> Option<char> {
+        self.text[self.offset..].chars().nth(1)
+    }
+
+    pub fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.offset += c.len_utf8();
+        if c == '\n' {
+            self.pos.line += 1;
+            self.pos.column = 0;
+        } else {
+            self.pos.column += c.len_utf16() as u32;
+        }
+
+        Some(c)
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn pos(&self) -> Pos {
+        self.pos
+    }
+
+    pub fn slice(&self, start: usize) -> &'a str {
+        &self.text[start..self.offset]
+    }
+}
@@ -0,0 +1,409 @@
+//! A position-preserving reader for master (zone) files.
+//!
+//! Unlike `dns_master_load` this keeps track of where every record was
+//! written, which is what editor features need. It does not validate
+//! anything; use [`crate::bind::zone::DnsZone`] for that.
+
+use super::{Cursor, Pos};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Word,
+    Quoted,
+    OpenParen,
+    CloseParen,
+    Comment,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+impl Token {
+    /// The token text without surrounding quotes.
+    pub fn value(&self) -> &str {
+        match self.kind {
+            TokenKind::Quoted => self
+                .text
+                .strip_prefix('"')
+                .map(|s| s.strip_suffix('"').unwrap_or(s))
+                .unwrap_or(&self.text),
+            _ => &self.text,
+        }
+    }
+}
+
+pub fn lex(text: &str) -> Vec<Token> {
+    let mut tokens = vec![];
+    let mut cursor = Cursor::new(text);
+
+    while let Some(c) = cursor.peek() {
+        let start = cursor.pos();
+        let offset = cursor.offset();
+
+        let kind = match c {
+            c if c.is_whitespace() => {
+                cursor.bump();
+                continue;
+            }
+            ';' => {
+                while cursor.peek().is_some_and(|c| c != '\n') {
+                    cursor.bump();
+                }
+                TokenKind::Comment
+            }
+            '(' => {
+                cursor.bump();
+                TokenKind::OpenParen
+            }
+            ')' => {
+                cursor.bump();
+                TokenKind::CloseParen
+            }
+            '"' => {
+                cursor.bump();
+                while let Some(c) = cursor.bump() {
+                    match c {
+                        '\\' => {
+                            cursor.bump();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+                TokenKind::Quoted
+            }
+            _ => {
+                while let Some(c) = cursor.peek() {
+                    if c.is_whitespace() || matches!(c, ';' | '(' | ')' | '"') {
+                        break;
+                    }
+                    cursor.bump();
+                    if c == '\\' {
+                        cursor.bump();
+                    }
+                }
+                TokenKind::Word
+            }
+        };
+
+        tokens.push(Token {
+            kind,
+            text: cursor.slice(offset).to_string(),
+            start,
+            end: cursor.pos(),
+        });
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone)]
+pub struct Record {
+    /// The owner name, made absolute when the origin is known.
+    pub owner: String,
+    /// The owner token, or `None` if the owner was inherited from the
+    /// previous record.
+    pub owner_token: Option<Token>,
+    /// The effective TTL, explicit or inherited from `$TTL`.
+    pub ttl: Option<u32>,
+    pub ttl_token: Option<Token>,
+    pub class_token: Option<Token>,
+    pub rtype: String,
+    pub type_token: Token,
+    pub rdata: Vec<Token>,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+#[derive(Debug, Clone)]
+pub struct Directive {
+    pub name: Token,
+    pub args: Vec<Token>,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+#[derive(Debug, Clone)]
+pub struct Include {
+    pub path: String,
+    /// The origin the included file starts with.
+    pub origin: Option<String>,
+    pub start: Pos,
+    pub end: Pos,
+}
+
+#[derive(Debug, Default)]
+pub struct ZoneText {
+    pub tokens: Vec<Token>,
+    pub records: Vec<Record>,
+    pub directives: Vec<Directive>,
+    pub includes: Vec<Include>,
+}
+
+/// Reads a master file, honoring `$ORIGIN`, `$TTL`, inherited owners and
+/// parenthesized records. `$INCLUDE` directives are reported but not
+/// followed.
+pub fn parse(text: &str, origin: Option<&str>) -> ZoneText {
+    let tokens = lex(text);
+
+    let mut zone = ZoneText::default();
+    let mut origin = origin.map(|o| absolute(o, Some(".")));
+    let mut default_ttl = None;
+    let mut last_ttl = None;
+    let mut last_owner: Option<String> = None;
+
+    for line in logical_lines(&tokens) {
+        let start = line[0].start;
+        let end = line[line.len() - 1].end;
+        let words = line
+            .iter()
+            .copied()
+            .filter(|t| matches!(t.kind, TokenKind::Word | TokenKind::Quoted))
+            .collect::<Vec<_>>();
+        let Some(&first) = words.first() else {
+            continue;
+        };
+
+        if first.start.column == 0 && first.kind == TokenKind::Word && first.text.starts_with('$') {
+            let args = words[1..].iter().map(|&t| t.clone()).collect::<Vec<_>>();
+            match first.text.to_ascii_uppercase().as_str() {
+                "$ORIGIN" => {
+                    if let Some(name) = args.first() {
+                        origin = Some(absolute(name.value(), origin.as_deref()));
+                    }
+                }
+                "$TTL" => {
+                    default_ttl = args.first().and_then(|t| parse_ttl(t.value()));
+                }
+                "$INCLUDE" => {
+                    if let Some(path) = args.first() {
+                        zone.includes.push(Include {
+                            path: path.value().to_string(),
+                            origin: match args.get(1) {
+                                Some(name) => Some(absolute(name.value(), origin.as_deref())),
+                                None => origin.clone(),
+                            },
+                            start,
+                            end,
+                        });
+                    }
+                }
+                _ => {}
+            }
+
+            zone.directives.push(Directive {
+                name: first.clone(),
+                args,
+                start,
+                end,
+            });
+            continue;
+        }
+
+        let (owner, owner_token, rest) = if first.start.column == 0 {
+            let owner = absolute(first.value(), origin.as_deref());
+            (owner, Some(first.clone()), &words[1..])
+        } else {
+            match &last_owner {
+                Some(owner) => (owner.clone(), None, &words[..]),
+                None => continue,
+            }
+        };
+        last_owner = Some(owner.clone());
+
+        let mut ttl_token = None;
+        let mut class_token = None;
+        let mut rest = rest.iter();
+        let type_token = loop {
+            let Some(&token) = rest.next() else {
+                break None;
+            };
+
+            if ttl_token.is_none() && parse_ttl(token.value()).is_some() {
+                ttl_token = Some(token.clone());
+            } else if class_token.is_none() && is_class(token.value()) {
+                class_token = Some(token.clone());
+            } else {
+                break Some(token.clone());
+            }
+        };
+        let Some(type_token) = type_token else {
+            continue;
+        };
+
+        let ttl = match &ttl_token {
+            Some(token) => {
+                last_ttl = parse_ttl(token.value());
+                last_ttl
+            }
+            None => default_ttl.or(last_ttl),
+        };
+
+        zone.records.push(Record {
+            owner,
+            owner_token,
+            ttl,
+            ttl_token,
+            class_token,
+            rtype: type_token.text.to_ascii_uppercase(),
+            type_token,
+            rdata: rest.map(|&t| t.clone()).collect(),
+            start,
+            end,
+        });
+    }
+
+    zone.tokens = tokens;
+    zone
+}
+
+/// Groups tokens into logical lines: a line break only ends a record when
+/// it is not inside parentheses. Comments are dropped.
+fn logical_lines(tokens: &[Token]) -> Vec<Vec<&Token>> {
+    let mut lines: Vec<Vec<&Token>> = vec![];
+    let mut depth = 0;
+    let mut last_line = None;
+
+    for token in tokens.iter().filter(|t| t.kind != TokenKind::Comment) {
+        if depth == 0 && last_line != Some(token.start.line) {
+            lines.push(vec![]);
+        }
+
+        match token.kind {
+            TokenKind::OpenParen => depth += 1,
+            TokenKind::CloseParen => depth = (depth - 1).max(0),
+            _ => {}
+        }
+
+        last_line = Some(token.end.line);
+        if let Some(line) = lines.last_mut() {
+            line.push(token);
+        }
+    }
+
+    lines
+}
+
+/// Makes `name` absolute relative to `origin`. Names stay relative when
+/// the origin is unknown.
+pub fn absolute(name: &str, origin: Option<&str>) -> String {
+    if name == "@" {
+        return origin.unwrap_or("@").to_string();
+    }
+    if name.ends_with('.') && !name.ends_with("\\.") {
+        return name.to_string();
+    }
+
+    match origin {
+        Some(".") => format!("{}.", name),
+        Some(origin) => format!("{}.{}", name, origin),
+        None => name.to_string(),
+    }
+}
+
+/// Parses a TTL either as plain seconds or in BIND's `1w2d3h4m5s` form.
+pub fn parse_ttl(s: &str) -> Option<u32> {
+    if s.is_empty() {
+        return None;
+    }
+    if let Ok(ttl) = s.parse() {
+        return Some(ttl);
+    }
+
+    let mut total: u32 = 0;
+    let mut value: Option<u32> = None;
+    for c in s.chars() {
+        if let Some(d) = c.to_digit(10) {
+            value = Some(value.unwrap_or(0).checked_mul(10)?.checked_add(d)?);
+            continue;
+        }
+
+        let unit = match c.to_ascii_lowercase() {
+            'w' => 604800,
+            'd' => 86400,
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        total = total.checked_add(value.take()?.checked_mul(unit)?)?;
+    }
+
+    match value {
+        Some(_) => None,
+        None => Some(total),
+    }
+}
+
+pub fn is_class(s: &str) -> bool {
+    let s = s.to_ascii_uppercase();
+    matches!(
+        s.as_str(),
+        "IN" | "CH" | "CHAOS" | "HS" | "HESIOD" | "CS" | "ANY" | "NONE"
+    ) || s
+        .strip_prefix("CLASS")
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ttl() {
+        assert_eq!(parse_ttl("3600"), Some(3600));
+        assert_eq!(parse_ttl("1h"), Some(3600));
+        assert_eq!(parse_ttl("1w2d"), Some(777600));
+        assert_eq!(parse_ttl("1H30M"), Some(5400));
+        assert_eq!(parse_ttl("1h30"), None);
+        assert_eq!(parse_ttl("IN"), None);
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = r#"$TTL 1h
+@   IN  SOA ns1 admin (
+            2023101001 ; Serial
+            3600 1800 604800 86400 )
+    IN  NS  ns1
+ns1 300 IN  A 192.168.1.1
+$ORIGIN sub.example.com.
+www     CNAME ns1.example.com.
+$INCLUDE "other.db" other.example.com.
+"#;
+
+        let zone = parse(text, Some("example.com"));
+        let records = zone
+            .records
+            .iter()
+            .map(|r| (r.owner.as_str(), r.rtype.as_str(), r.ttl))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            records,
+            vec![
+                ("example.com.", "SOA", Some(3600)),
+                ("example.com.", "NS", Some(3600)),
+                ("ns1.example.com.", "A", Some(300)),
+                ("www.sub.example.com.", "CNAME", Some(3600)),
+            ]
+        );
+
+        let soa = &zone.records[0];
+        assert_eq!(soa.rdata.len(), 7);
+        assert_eq!(soa.rdata[2].text, "2023101001");
+        assert_eq!(soa.end.line, 3);
+
+        assert_eq!(zone.includes.len(), 1);
+        assert_eq!(zone.includes[0].path, "other.db");
+        assert_eq!(
+            zone.includes[0].origin.as_deref(),
+            Some("other.example.com.")
+        );
+    }
+}
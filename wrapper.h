@@ -6,9 +6,23 @@
 #include <isc/log.h>
 #include <dns/log.h>
 #include <dns/types.h>
+#include <dns/db.h>
+#include <dns/dbiterator.h>
+#include <dns/diff.h>
+#include <dns/fixedname.h>
+#include <dns/journal.h>
+#include <dns/master.h>
+#include <dns/masterdump.h>
+#include <dns/name.h>
+#include <dns/rdata.h>
+#include <dns/rdataclass.h>
+#include <dns/rdataset.h>
+#include <dns/rdatasetiter.h>
+#include <dns/rdatatype.h>
 #include <dns/zone.h>
 #include <ns/log.h>
 #include <isccfg/cfg.h>
 #include <isccfg/log.h>
 #include <isccfg/check.h>
+#include <isccfg/grammar.h>
 #include <isccfg/namedconf.h>